@@ -0,0 +1,71 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! public key algorithms for signing zone records
+
+use ::error::{DecodeResult, DecodeError};
+
+/// Algorithms of signing keys, as used by DNSSEC, see
+/// [RFC 8624, Algorithm Implementation Requirements, June 2019](https://tools.ietf.org/html/rfc8624)
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Algorithm {
+    /// RSA/SHA-256, see [RFC 5702](https://tools.ietf.org/html/rfc5702)
+    RSASHA256,
+    /// ECDSA Curve P-256 with SHA-256, see [RFC 6605](https://tools.ietf.org/html/rfc6605)
+    ECDSAP256SHA256,
+    /// ECDSA Curve P-384 with SHA-384, see [RFC 6605](https://tools.ietf.org/html/rfc6605)
+    ECDSAP384SHA384,
+    /// Edwards curve 25519, see [RFC 8080](https://tools.ietf.org/html/rfc8080)
+    ED25519,
+}
+
+impl Algorithm {
+    /// The IANA-assigned number for this algorithm, as carried in DNSKEY and RRSIG records.
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            Algorithm::RSASHA256 => 8,
+            Algorithm::ECDSAP256SHA256 => 13,
+            Algorithm::ECDSAP384SHA384 => 14,
+            Algorithm::ED25519 => 15,
+        }
+    }
+
+    /// Convert from the IANA-assigned algorithm number
+    pub fn from_u8(value: u8) -> DecodeResult<Self> {
+        match value {
+            8 => Ok(Algorithm::RSASHA256),
+            13 => Ok(Algorithm::ECDSAP256SHA256),
+            14 => Ok(Algorithm::ECDSAP384SHA384),
+            15 => Ok(Algorithm::ED25519),
+            _ => Err(DecodeError::UnknownAlgorithmTypeValue(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for algorithm in &[Algorithm::RSASHA256,
+                            Algorithm::ECDSAP256SHA256,
+                            Algorithm::ECDSAP384SHA384,
+                            Algorithm::ED25519] {
+            assert_eq!(*algorithm, Algorithm::from_u8(algorithm.to_u8()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! DNSSEC related modules: algorithms, signing, and their record data
+
+pub mod algorithm;
+pub mod signer;
+pub mod supported_algorithms;
+pub mod rdata;
+
+pub use self::algorithm::Algorithm;
+pub use self::signer::Signer;
+pub use self::supported_algorithms::{SupportedAlgorithms, SupportedDigests};
@@ -0,0 +1,139 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! DNSKEY record data, publishing a zone's public signing key, as specified in
+//! [RFC 4034, Resource Records for the DNS Security Extensions, March 2005](https://tools.ietf.org/html/rfc4034#section-2)
+
+use ::rr::dnssec::Algorithm;
+use ::serialize::binary::*;
+use ::error::*;
+
+/// Set in `flags` for every DNSSEC zone key (RFC 4034 Appendix A.1, the "Zone Key" bit)
+const ZONE_KEY_FLAG: u16 = 0x0100;
+/// Additionally set in `flags` for a key-signing key, by convention (RFC 4034 Appendix A.1,
+/// the "Secure Entry Point" bit)
+const SEP_FLAG: u16 = 0x0001;
+
+/// The DNSKEY record data: a zone's public key, tagged with the algorithm it signs with and
+/// whether it's a key-signing key or a zone-signing key.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DNSKEY {
+    flags: u16,
+    algorithm: Algorithm,
+    public_key: Vec<u8>,
+}
+
+impl DNSKEY {
+    /// Constructs a new DNSKEY rdata. `is_zone_signing_key` controls only the advisory Secure
+    /// Entry Point bit (true for a ZSK's flags, false for a KSK's); the Zone Key bit is always
+    /// set, since every key this crate signs with is a zone key.
+    pub fn new(is_zone_signing_key: bool, algorithm: Algorithm, public_key: Vec<u8>) -> Self {
+        let flags = if is_zone_signing_key {
+            ZONE_KEY_FLAG
+        } else {
+            ZONE_KEY_FLAG | SEP_FLAG
+        };
+
+        DNSKEY {
+            flags: flags,
+            algorithm: algorithm,
+            public_key: public_key,
+        }
+    }
+
+    /// The raw flags field
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// True if the Secure Entry Point bit is unset, i.e. this key was constructed as a
+    /// zone-signing key rather than a key-signing key
+    pub fn is_zone_signing_key(&self) -> bool {
+        self.flags & SEP_FLAG == 0
+    }
+
+    /// The algorithm this key signs with
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The public key material, encoded per the algorithm's own RFC (e.g. RFC 3110 for RSA,
+    /// RFC 6605 for ECDSA, RFC 8080 for EdDSA)
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+/// Reads a DNSKEY's RDATA; `rdata_length` bounds the trailing public key field, which has no
+/// length prefix of its own.
+pub fn read(decoder: &mut BinDecoder, rdata_length: u16) -> DecodeResult<DNSKEY> {
+    let flags = try!(decoder.read_u16());
+    let _protocol = try!(decoder.pop()); // always 3, RFC 4034 §2.1.2
+    let algorithm = try!(Algorithm::from_u8(try!(decoder.pop())));
+    let public_key_len = (rdata_length as usize).saturating_sub(4);
+    let public_key = try!(decoder.read_vec(public_key_len));
+
+    Ok(DNSKEY {
+        flags: flags,
+        algorithm: algorithm,
+        public_key: public_key,
+    })
+}
+
+/// Writes a DNSKEY's RDATA
+pub fn emit(encoder: &mut BinEncoder, dnskey: &DNSKEY) -> EncodeResult {
+    try!(encoder.emit_u16(dnskey.flags()));
+    try!(encoder.emit(3)); // protocol, always 3
+    try!(encoder.emit(dnskey.algorithm().to_u8()));
+    encoder.emit_vec(dnskey.public_key())
+}
+
+#[cfg(test)]
+mod mytests {
+    use ::rr::dnssec::Algorithm;
+    use ::serialize::binary::*;
+
+    use super::*;
+
+    fn get_data() -> Vec<DNSKEY> {
+        vec![DNSKEY::new(true, Algorithm::RSASHA256, vec![]), // base case, empty key, ZSK
+             DNSKEY::new(false, Algorithm::ECDSAP256SHA256, vec![1, 2, 3, 4, 5, 6, 7, 8]), // KSK
+             DNSKEY::new(true, Algorithm::ED25519, (0..32).collect())]
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        for dnskey in get_data() {
+            let mut bytes = Vec::new();
+            {
+                let mut encoder = BinEncoder::new(&mut bytes);
+                emit(&mut encoder, &dnskey).expect("failed to emit DNSKEY");
+            }
+
+            let mut decoder = BinDecoder::new(&bytes);
+            let read_dnskey = read(&mut decoder, bytes.len() as u16).expect("failed to read DNSKEY");
+            assert_eq!(dnskey, read_dnskey);
+        }
+    }
+
+    #[test]
+    fn test_is_zone_signing_key() {
+        let zsk = DNSKEY::new(true, Algorithm::RSASHA256, vec![]);
+        let ksk = DNSKEY::new(false, Algorithm::RSASHA256, vec![]);
+        assert!(zsk.is_zone_signing_key());
+        assert!(!ksk.is_zone_signing_key());
+    }
+}
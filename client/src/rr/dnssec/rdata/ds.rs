@@ -0,0 +1,113 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! DS record data, which carries a delegated child zone's key digest, as specified in
+//! [RFC 4034, Resource Records for the DNS Security Extensions, March 2005](https://tools.ietf.org/html/rfc4034#section-5)
+
+use ::rr::dnssec::Algorithm;
+use ::serialize::binary::*;
+use ::error::*;
+
+/// The DS record data: a reference to a child zone's DNSKEY, identified by key tag, algorithm,
+/// and a digest of the key rather than the key itself.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DS {
+    key_tag: u16,
+    algorithm: Algorithm,
+    digest_type: u8,
+    digest: Vec<u8>,
+}
+
+impl DS {
+    /// Constructs a new DS rdata
+    pub fn new(key_tag: u16, algorithm: Algorithm, digest_type: u8, digest: Vec<u8>) -> Self {
+        DS {
+            key_tag: key_tag,
+            algorithm: algorithm,
+            digest_type: digest_type,
+            digest: digest,
+        }
+    }
+
+    /// The key tag of the DNSKEY this DS refers to
+    pub fn key_tag(&self) -> u16 {
+        self.key_tag
+    }
+
+    /// The algorithm of the DNSKEY this DS refers to
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The IANA digest algorithm number used to produce `digest`
+    pub fn digest_type(&self) -> u8 {
+        self.digest_type
+    }
+
+    /// The digest of the referenced DNSKEY's RDATA
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+}
+
+/// Reads a DS's RDATA; `rdata_length` bounds the trailing digest field, which has no length
+/// prefix of its own.
+pub fn read(decoder: &mut BinDecoder, rdata_length: u16) -> DecodeResult<DS> {
+    let key_tag = try!(decoder.read_u16());
+    let algorithm = try!(Algorithm::from_u8(try!(decoder.pop())));
+    let digest_type = try!(decoder.pop());
+    let digest_len = (rdata_length as usize).saturating_sub(4);
+    let digest = try!(decoder.read_vec(digest_len));
+
+    Ok(DS::new(key_tag, algorithm, digest_type, digest))
+}
+
+/// Writes a DS's RDATA
+pub fn emit(encoder: &mut BinEncoder, ds: &DS) -> EncodeResult {
+    try!(encoder.emit_u16(ds.key_tag()));
+    try!(encoder.emit(ds.algorithm().to_u8()));
+    try!(encoder.emit(ds.digest_type()));
+    encoder.emit_vec(ds.digest())
+}
+
+#[cfg(test)]
+mod mytests {
+    use ::rr::dnssec::Algorithm;
+    use ::serialize::binary::*;
+
+    use super::*;
+
+    fn get_data() -> Vec<DS> {
+        vec![DS::new(0, Algorithm::RSASHA256, 1, vec![]), // base case, empty digest
+             DS::new(12345, Algorithm::ECDSAP256SHA256, 2, vec![1, 2, 3, 4, 5, 6, 7, 8]),
+             DS::new(65535, Algorithm::ED25519, 4, (0..64).collect())]
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        for ds in get_data() {
+            let mut bytes = Vec::new();
+            {
+                let mut encoder = BinEncoder::new(&mut bytes);
+                emit(&mut encoder, &ds).expect("failed to emit DS");
+            }
+
+            let mut decoder = BinDecoder::new(&bytes);
+            let read_ds = read(&mut decoder, bytes.len() as u16).expect("failed to read DS");
+            assert_eq!(ds, read_ds);
+        }
+    }
+}
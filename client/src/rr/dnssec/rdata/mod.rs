@@ -0,0 +1,27 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! record data enum variants for DNSSEC related records
+
+pub mod sig;
+pub mod nsec;
+pub mod ds;
+pub mod dnskey;
+
+pub use self::sig::SIG;
+pub use self::nsec::NSEC;
+pub use self::ds::DS;
+pub use self::dnskey::DNSKEY;
@@ -0,0 +1,218 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! SIG/RRSIG record data, as specified in
+//! [RFC 4034, Resource Records for the DNS Security Extensions, March 2005](https://tools.ietf.org/html/rfc4034#section-3)
+//!
+//! ```text
+//! 3.1.  RRSIG RDATA Wire Format
+//!
+//!    The RDATA for an RRSIG RR consists of a 2 octet Type Covered field, a
+//!    1 octet Algorithm field, a 1 octet Labels field, a 4 octet Original
+//!    TTL field, a 4 octet Signature Expiration field, a 4 octet Signature
+//!    Inception field, a 2 octet Key Tag field, the Signer's Name field,
+//!    and the Signature field.
+//! ```
+
+use ::rr::{Name, RecordType};
+use ::rr::dnssec::Algorithm;
+use ::serialize::binary::*;
+use ::error::*;
+
+/// The RRSIG record data. `SIG` is the historical (pre-DNSSEC) name for the same wire format,
+/// reused here since RRSIG is just SIG applied to DNSSEC-signed RRsets.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SIG {
+    type_covered: RecordType,
+    algorithm: Algorithm,
+    num_labels: u8,
+    original_ttl: u32,
+    sig_expiration: u32,
+    sig_inception: u32,
+    key_tag: u16,
+    signer_name: Name,
+    sig: Vec<u8>,
+}
+
+impl SIG {
+    /// Constructs a new RRSIG rdata. Pass an empty `sig` when building the RDATA that is about
+    /// to be signed; fill it in with the real signature afterwards via `new` again.
+    #[allow(too_many_arguments)]
+    pub fn new(type_covered: RecordType,
+               algorithm: Algorithm,
+               num_labels: u8,
+               original_ttl: u32,
+               sig_expiration: u32,
+               sig_inception: u32,
+               key_tag: u16,
+               signer_name: Name,
+               sig: Vec<u8>)
+               -> Self {
+        SIG {
+            type_covered: type_covered,
+            algorithm: algorithm,
+            num_labels: num_labels,
+            original_ttl: original_ttl,
+            sig_expiration: sig_expiration,
+            sig_inception: sig_inception,
+            key_tag: key_tag,
+            signer_name: signer_name,
+            sig: sig,
+        }
+    }
+
+    /// The RRset type this signature covers
+    pub fn type_covered(&self) -> RecordType {
+        self.type_covered
+    }
+
+    /// The algorithm used to produce the signature
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The number of labels in the original, non-wildcard-expanded owner name
+    pub fn num_labels(&self) -> u8 {
+        self.num_labels
+    }
+
+    /// The TTL of the covered RRset at the time of signing
+    pub fn original_ttl(&self) -> u32 {
+        self.original_ttl
+    }
+
+    /// The point after which this signature is no longer valid
+    pub fn sig_expiration(&self) -> u32 {
+        self.sig_expiration
+    }
+
+    /// The point before which this signature is not yet valid
+    pub fn sig_inception(&self) -> u32 {
+        self.sig_inception
+    }
+
+    /// The key tag of the DNSKEY that can verify this signature
+    pub fn key_tag(&self) -> u16 {
+        self.key_tag
+    }
+
+    /// The owner name of the DNSKEY that can verify this signature
+    pub fn signer_name(&self) -> &Name {
+        &self.signer_name
+    }
+
+    /// The cryptographic signature itself
+    pub fn sig(&self) -> &[u8] {
+        &self.sig
+    }
+}
+
+/// Reads an RRSIG's RDATA; `rdata_length` bounds how many trailing bytes belong to the
+/// signature field, since it has no length prefix of its own.
+pub fn read(decoder: &mut BinDecoder, rdata_length: u16) -> DecodeResult<SIG> {
+    let start_index = decoder.index();
+
+    let type_covered = try!(RecordType::from_u16(try!(decoder.read_u16())));
+    let algorithm = try!(Algorithm::from_u8(try!(decoder.pop())));
+    let num_labels = try!(decoder.pop());
+    let original_ttl = try!(decoder.read_u32());
+    let sig_expiration = try!(decoder.read_u32());
+    let sig_inception = try!(decoder.read_u32());
+    let key_tag = try!(decoder.read_u16());
+    let signer_name = try!(Name::read(decoder));
+
+    let consumed = decoder.index() - start_index;
+    let sig_len = (rdata_length as usize).saturating_sub(consumed);
+    let sig = try!(decoder.read_vec(sig_len));
+
+    Ok(SIG::new(type_covered,
+                algorithm,
+                num_labels,
+                original_ttl,
+                sig_expiration,
+                sig_inception,
+                key_tag,
+                signer_name,
+                sig))
+}
+
+/// Writes an RRSIG's RDATA. When `sig.sig()` is empty (as when building the record that is
+/// about to be signed) this produces exactly the "to be signed" prefix that precedes the
+/// canonically ordered RRset, per RFC 4034 §3.1.8.1.
+pub fn emit(encoder: &mut BinEncoder, sig: &SIG) -> EncodeResult {
+    try!(encoder.emit_u16(sig.type_covered().to_u16()));
+    try!(encoder.emit(sig.algorithm().to_u8()));
+    try!(encoder.emit(sig.num_labels()));
+    try!(encoder.emit_u32(sig.original_ttl()));
+    try!(encoder.emit_u32(sig.sig_expiration()));
+    try!(encoder.emit_u32(sig.sig_inception()));
+    try!(encoder.emit_u16(sig.key_tag()));
+    try!(sig.signer_name().to_lowercase().emit(encoder));
+    encoder.emit_vec(sig.sig())
+}
+
+#[cfg(test)]
+mod mytests {
+    use ::serialize::binary::*;
+
+    use super::*;
+
+    fn get_data() -> Vec<SIG> {
+        vec![SIG::new(RecordType::A, // base case, empty signature
+                       Algorithm::RSASHA256,
+                       2,
+                       86400,
+                       0,
+                       0,
+                       0,
+                       Name::new(),
+                       vec![]),
+             SIG::new(RecordType::NS,
+                       Algorithm::ECDSAP256SHA256,
+                       3,
+                       3600,
+                       1893456000,
+                       1861920000,
+                       12345,
+                       Name::new().label("example").label("com"),
+                       vec![1, 2, 3, 4, 5, 6, 7, 8])]
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        for sig in get_data() {
+            let mut bytes = Vec::new();
+            {
+                let mut encoder = BinEncoder::new(&mut bytes);
+                emit(&mut encoder, &sig).expect("failed to emit SIG");
+            }
+
+            let mut decoder = BinDecoder::new(&bytes);
+            let read_sig = read(&mut decoder, bytes.len() as u16).expect("failed to read SIG");
+
+            // `signer_name` is emitted lowercased; everything else should round-trip exactly
+            assert_eq!(sig.signer_name().to_lowercase(), *read_sig.signer_name());
+            assert_eq!(sig.type_covered(), read_sig.type_covered());
+            assert_eq!(sig.algorithm(), read_sig.algorithm());
+            assert_eq!(sig.num_labels(), read_sig.num_labels());
+            assert_eq!(sig.original_ttl(), read_sig.original_ttl());
+            assert_eq!(sig.sig_expiration(), read_sig.sig_expiration());
+            assert_eq!(sig.sig_inception(), read_sig.sig_inception());
+            assert_eq!(sig.key_tag(), read_sig.key_tag());
+            assert_eq!(sig.sig(), read_sig.sig());
+        }
+    }
+}
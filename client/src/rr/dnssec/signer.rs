@@ -0,0 +1,445 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! signer is a structure for performing many of the signing processes of the DNSSEC specification
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use openssl::sign::Signer as OpenSslSigner;
+
+use ::error::{DnsSecResult, DnsSecErrorKind};
+use ::rr::Name;
+use ::rr::dnssec::Algorithm;
+use ::rr::dnssec::rdata::{self, DNSKEY};
+use ::serialize::binary::BinEncoder;
+
+/// How long an RRSIG stays valid after its inception when no other duration is given, as used
+/// by `Signer::with_defaults`.
+const DEFAULT_SIG_DURATION_SECS: u64 = 60 * 60 * 24 * 30; // 30 days
+
+/// Couples a private key with the metadata needed to produce an RRSIG: the algorithm it signs
+/// with, the name it signs as, how long a signature it produces should remain valid, and
+/// whether it's a key-signing key (which only signs the zone's DNSKEY RRset) or a
+/// zone-signing key (which signs everything else).
+pub struct Signer {
+    key: PKey,
+    algorithm: Algorithm,
+    signer_name: Name,
+    sig_duration: Duration,
+    key_tag: u16,
+    is_zone_signing_key: bool,
+}
+
+impl Signer {
+    /// Creates a new `Signer` which will produce RRSIGs naming `signer_name` as the signer,
+    /// valid for `sig_duration` from the moment each RRSIG is minted.
+    pub fn new(key: PKey,
+               algorithm: Algorithm,
+               signer_name: Name,
+               sig_duration: Duration,
+               is_zone_signing_key: bool)
+               -> Self {
+        let key_tag = Self::calculate_key_tag(&key, algorithm, is_zone_signing_key);
+
+        Signer {
+            key: key,
+            algorithm: algorithm,
+            signer_name: signer_name,
+            sig_duration: sig_duration,
+            key_tag: key_tag,
+            is_zone_signing_key: is_zone_signing_key,
+        }
+    }
+
+    /// Creates a new `Signer` using the repo-wide default 30 day signature validity window.
+    pub fn with_defaults(key: PKey, algorithm: Algorithm, signer_name: Name, is_zone_signing_key: bool) -> Self {
+        Self::new(key,
+                  algorithm,
+                  signer_name,
+                  Duration::from_secs(DEFAULT_SIG_DURATION_SECS),
+                  is_zone_signing_key)
+    }
+
+    /// Loads the private key at `key_path` (generating one for `algorithm` and persisting it
+    /// there first, if the path doesn't exist yet), and wraps it as a `Signer` naming
+    /// `signer_name` with the repo-wide default signature validity window.
+    ///
+    /// This is the entry point a declarative per-zone key config uses to turn a key path on
+    /// disk into a usable `Signer`, rather than requiring a key to already be loaded in memory.
+    pub fn from_key_path(key_path: &Path,
+                         algorithm: Algorithm,
+                         signer_name: Name,
+                         is_zone_signing_key: bool)
+                         -> DnsSecResult<Self> {
+        let key = if key_path.exists() {
+            try!(Self::read_key(key_path))
+        } else {
+            let key = try!(Self::generate_key(algorithm));
+            try!(Self::write_key(key_path, &key));
+            key
+        };
+
+        Ok(Self::with_defaults(key, algorithm, signer_name, is_zone_signing_key))
+    }
+
+    /// Reads a private key from `path`, accepting either PEM or raw PKCS#8 DER encoding.
+    fn read_key(path: &Path) -> DnsSecResult<PKey> {
+        let mut file = try!(File::open(path)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to open {}: {}", path.display(), e))));
+        let mut bytes = Vec::new();
+        try!(file.read_to_end(&mut bytes)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to read {}: {}", path.display(), e))));
+
+        PKey::private_key_from_pem(&bytes)
+            .or_else(|_| PKey::private_key_from_der(&bytes))
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to parse key at {}: {}", path.display(), e)).into())
+    }
+
+    /// Generates a fresh private key appropriate for `algorithm`
+    fn generate_key(algorithm: Algorithm) -> DnsSecResult<PKey> {
+        match algorithm {
+            Algorithm::RSASHA256 => {
+                let rsa = try!(Rsa::generate(2048)
+                    .map_err(|e| DnsSecErrorKind::Msg(format!("RSA key generation failed: {}", e))));
+                PKey::from_rsa(rsa).map_err(|e| DnsSecErrorKind::Msg(format!("{}", e)).into())
+            }
+            Algorithm::ECDSAP256SHA256 => {
+                let group = try!(EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+                    .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+                let ec_key = try!(EcKey::generate(&group).map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+                PKey::from_ec_key(ec_key).map_err(|e| DnsSecErrorKind::Msg(format!("{}", e)).into())
+            }
+            Algorithm::ECDSAP384SHA384 => {
+                let group = try!(EcGroup::from_curve_name(Nid::SECP384R1)
+                    .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+                let ec_key = try!(EcKey::generate(&group).map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+                PKey::from_ec_key(ec_key).map_err(|e| DnsSecErrorKind::Msg(format!("{}", e)).into())
+            }
+            Algorithm::ED25519 => {
+                PKey::generate_ed25519().map_err(|e| DnsSecErrorKind::Msg(format!("{}", e)).into())
+            }
+        }
+    }
+
+    /// Persists `key` to `path` as PEM-encoded PKCS#8, so a later run of the same config
+    /// reloads the identical key instead of generating a new one.
+    fn write_key(path: &Path, key: &PKey) -> DnsSecResult<()> {
+        let pem = try!(key.private_key_to_pem_pkcs8()
+            .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+        let mut file = try!(File::create(path)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to create {}: {}", path.display(), e))));
+        file.write_all(&pem)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to write {}: {}", path.display(), e)).into())
+    }
+
+    /// The algorithm used by this key
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The name that will appear in the `signer name` field of RRSIGs produced by this `Signer`
+    pub fn signer_name(&self) -> &Name {
+        &self.signer_name
+    }
+
+    /// How long from "now" a freshly minted RRSIG should remain valid
+    pub fn sig_duration(&self) -> Duration {
+        self.sig_duration
+    }
+
+    /// The DNSKEY key tag for this key, per
+    /// [RFC 4034, Appendix B](https://tools.ietf.org/html/rfc4034#appendix-B)
+    pub fn key_tag(&self) -> u16 {
+        self.key_tag
+    }
+
+    /// True for a zone-signing key (signs every RRset but DNSKEY), false for a key-signing
+    /// key (signs only the zone's DNSKEY RRset)
+    pub fn is_zone_signing_key(&self) -> bool {
+        self.is_zone_signing_key
+    }
+
+    /// Builds the DNSKEY rdata this key should be published under, with the public key encoded
+    /// per its algorithm's own RFC so another validator's DNSKEY parser can read it.
+    pub fn to_dnskey(&self) -> DnsSecResult<DNSKEY> {
+        let public_key = try!(Self::encode_public_key(&self.key, self.algorithm));
+        Ok(DNSKEY::new(self.is_zone_signing_key, self.algorithm, public_key))
+    }
+
+    /// Encodes `key`'s public half into the wire format its DNSKEY RDATA is supposed to carry:
+    /// [RFC 3110](https://tools.ietf.org/html/rfc3110) for RSA, [RFC 6605](https://tools.ietf.org/html/rfc6605)
+    /// for ECDSA, [RFC 8080](https://tools.ietf.org/html/rfc8080) for EdDSA.
+    fn encode_public_key(key: &PKey, algorithm: Algorithm) -> DnsSecResult<Vec<u8>> {
+        match algorithm {
+            Algorithm::RSASHA256 => {
+                let rsa = try!(key.rsa().map_err(|e| DnsSecErrorKind::Msg(format!("not an RSA key: {}", e))));
+                let exponent = rsa.e().to_vec();
+                let modulus = rsa.n().to_vec();
+
+                // RFC 3110 §2: a one-byte exponent length (or, if that doesn't fit, a zero byte
+                // followed by a two-byte length), the exponent, then the modulus.
+                let mut public_key = Vec::with_capacity(3 + exponent.len() + modulus.len());
+                if exponent.len() <= 0xFF {
+                    public_key.push(exponent.len() as u8);
+                } else {
+                    public_key.push(0);
+                    public_key.push((exponent.len() >> 8) as u8);
+                    public_key.push((exponent.len() & 0xFF) as u8);
+                }
+                public_key.extend_from_slice(&exponent);
+                public_key.extend_from_slice(&modulus);
+                Ok(public_key)
+            }
+            Algorithm::ECDSAP256SHA256 => Self::encode_ec_public_key(key, 32),
+            Algorithm::ECDSAP384SHA384 => Self::encode_ec_public_key(key, 48),
+            Algorithm::ED25519 => {
+                // RFC 8080 §3: the raw 32-byte public key, with no further framing -- exactly
+                // what `raw_public_key` already returns for an Ed25519 `PKey`.
+                key.raw_public_key()
+                    .map_err(|e| DnsSecErrorKind::Msg(format!("failed to read Ed25519 public key: {}", e)).into())
+            }
+        }
+    }
+
+    /// Encodes an ECDSA public key as the concatenated big-endian `x || y` affine coordinates,
+    /// each padded out to `field_size` bytes, per RFC 6605 §4 (no leading point-format octet,
+    /// unlike the SEC1 encoding OpenSSL itself uses).
+    fn encode_ec_public_key(key: &PKey, field_size: usize) -> DnsSecResult<Vec<u8>> {
+        let ec_key = try!(key.ec_key().map_err(|e| DnsSecErrorKind::Msg(format!("not an EC key: {}", e))));
+        let mut ctx = try!(BigNumContext::new().map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+        let mut x = try!(BigNum::new().map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+        let mut y = try!(BigNum::new().map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+        try!(ec_key.public_key()
+            .affine_coordinates(ec_key.group(), &mut x, &mut y, &mut ctx)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to read EC public key: {}", e))));
+
+        let mut public_key = vec![0u8; field_size * 2];
+        let x_bytes = x.to_vec();
+        let y_bytes = y.to_vec();
+        public_key[field_size - x_bytes.len()..field_size].copy_from_slice(&x_bytes);
+        public_key[2 * field_size - y_bytes.len()..2 * field_size].copy_from_slice(&y_bytes);
+        Ok(public_key)
+    }
+
+    /// The [RFC 4034, Appendix B](https://tools.ietf.org/html/rfc4034#appendix-B) key tag
+    /// algorithm, summed over the wire-encoded DNSKEY RDATA this key would publish -- flags,
+    /// protocol, algorithm, and the RFC 3110/6605/8080-encoded public key. The owner name never
+    /// enters a real DNSKEY's RDATA, so it plays no part here either; a compliant validator
+    /// computing the tag from the published DNSKEY has to land on the same value.
+    fn calculate_key_tag(key: &PKey, algorithm: Algorithm, is_zone_signing_key: bool) -> u16 {
+        let public_key = Self::encode_public_key(key, algorithm).unwrap_or_default();
+        let dnskey = DNSKEY::new(is_zone_signing_key, algorithm, public_key);
+
+        let mut rdata = Vec::new();
+        {
+            let mut encoder = BinEncoder::new(&mut rdata);
+            if rdata::dnskey::emit(&mut encoder, &dnskey).is_err() {
+                return 0;
+            }
+        }
+
+        let mut ac: u32 = 0;
+        for (i, byte) in rdata.iter().enumerate() {
+            ac += if i & 1 == 1 {
+                *byte as u32
+            } else {
+                (*byte as u32) << 8
+            };
+        }
+        ac += (ac >> 16) & 0xFFFF;
+        (ac & 0xFFFF) as u16
+    }
+
+    /// The digest algorithm used to sign with, for every algorithm but `ED25519`: EdDSA isn't
+    /// incremental and is signed one-shot with no digest of its own (see `sign`), so it has no
+    /// `MessageDigest` to return here.
+    fn digest(&self) -> MessageDigest {
+        match self.algorithm {
+            Algorithm::RSASHA256 |
+            Algorithm::ECDSAP256SHA256 => MessageDigest::sha256(),
+            Algorithm::ECDSAP384SHA384 => MessageDigest::sha384(),
+            Algorithm::ED25519 => unreachable!("ED25519 is signed one-shot in `sign`, without a digest"),
+        }
+    }
+
+    /// Signs `tbs` (the RRSIG RDATA minus the signature, followed by the canonically
+    /// ordered RRset), returning the raw signature bytes to place in the RRSIG's
+    /// signature field.
+    ///
+    /// `ED25519` takes a different path through OpenSSL than every other algorithm here: EdDSA
+    /// rejects a digest in `EVP_DigestSignInit` and can't be fed incrementally via
+    /// `update`/`finish`, so it's signed one-shot over the full message instead.
+    pub fn sign(&self, tbs: &[u8]) -> DnsSecResult<Vec<u8>> {
+        if self.algorithm == Algorithm::ED25519 {
+            let mut signer = try!(OpenSslSigner::new_without_digest(&self.key)
+                .map_err(|e| DnsSecErrorKind::Msg(format!("signer init failed: {}", e))));
+            return signer.sign_oneshot_to_vec(tbs)
+                .map_err(|e| DnsSecErrorKind::Msg(format!("signing failed: {}", e)).into());
+        }
+
+        let mut signer = try!(OpenSslSigner::new(self.digest(), &self.key)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("signer init failed: {}", e))));
+        try!(signer.update(tbs)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("signer update failed: {}", e))));
+        signer.finish()
+            .map_err(|e| DnsSecErrorKind::Msg(format!("signing failed: {}", e)).into())
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use std::time::Duration;
+
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::sign::Verifier;
+
+    use super::*;
+    use ::rr::Name;
+    use ::rr::dnssec::Algorithm;
+
+    #[test]
+    fn test_key_tag_is_stable() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let name = Name::new().label("example").label("com");
+
+        let signer_a = Signer::new(key.clone(), Algorithm::RSASHA256, name.clone(), Duration::from_secs(60), true);
+        let signer_b = Signer::new(key, Algorithm::RSASHA256, name, Duration::from_secs(60), true);
+
+        assert_eq!(signer_a.key_tag(), signer_b.key_tag());
+    }
+
+    #[test]
+    fn test_key_tag_ignores_signer_name() {
+        // RFC 4034 Appendix B computes the tag purely from the DNSKEY RDATA; the owner name
+        // under which it's published must not affect it.
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+
+        let signer_a = Signer::new(key.clone(),
+                                    Algorithm::RSASHA256,
+                                    Name::new().label("example").label("com"),
+                                    Duration::from_secs(60),
+                                    true);
+        let signer_b = Signer::new(key,
+                                    Algorithm::RSASHA256,
+                                    Name::new().label("otherwise").label("net"),
+                                    Duration::from_secs(60),
+                                    true);
+
+        assert_eq!(signer_a.key_tag(), signer_b.key_tag());
+    }
+
+    #[test]
+    fn test_key_tag_matches_independent_rfc4034_appendix_b_computation() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let name = Name::new().label("example").label("com");
+        let signer = Signer::new(key, Algorithm::RSASHA256, name, Duration::from_secs(60), true);
+
+        let dnskey = signer.to_dnskey().unwrap();
+        let mut rdata = Vec::new();
+        {
+            let mut encoder = BinEncoder::new(&mut rdata);
+            rdata::dnskey::emit(&mut encoder, &dnskey).unwrap();
+        }
+
+        let mut ac: u32 = 0;
+        for (i, byte) in rdata.iter().enumerate() {
+            ac += if i & 1 == 1 { *byte as u32 } else { (*byte as u32) << 8 };
+        }
+        ac += (ac >> 16) & 0xFFFF;
+
+        assert_eq!(signer.key_tag(), (ac & 0xFFFF) as u16);
+    }
+
+    #[test]
+    fn test_to_dnskey_sets_sep_flag_only_for_key_signing_key() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let name = Name::new().label("example").label("com");
+
+        let zsk = Signer::new(key.clone(), Algorithm::RSASHA256, name.clone(), Duration::from_secs(60), true);
+        let ksk = Signer::new(key, Algorithm::RSASHA256, name, Duration::from_secs(60), false);
+
+        assert!(zsk.to_dnskey().unwrap().is_zone_signing_key());
+        assert!(!ksk.to_dnskey().unwrap().is_zone_signing_key());
+    }
+
+    #[test]
+    fn test_to_dnskey_rsa_is_rfc3110_encoded() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let exponent = rsa.e().to_vec();
+        let modulus = rsa.n().to_vec();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let name = Name::new().label("example").label("com");
+        let signer = Signer::new(key, Algorithm::RSASHA256, name, Duration::from_secs(60), true);
+
+        let public_key = signer.to_dnskey().unwrap().public_key().to_vec();
+
+        assert_eq!(public_key[0] as usize, exponent.len());
+        assert_eq!(&public_key[1..1 + exponent.len()], &exponent[..]);
+        assert_eq!(&public_key[1 + exponent.len()..], &modulus[..]);
+    }
+
+    #[test]
+    fn test_to_dnskey_ed25519_is_the_raw_32_byte_key() {
+        let key = PKey::generate_ed25519().unwrap();
+        let raw = key.raw_public_key().unwrap();
+        let name = Name::new().label("example").label("com");
+        let signer = Signer::new(key, Algorithm::ED25519, name, Duration::from_secs(60), true);
+
+        assert_eq!(signer.to_dnskey().unwrap().public_key(), &raw[..]);
+    }
+
+    #[test]
+    fn test_sign_produces_a_signature_verifiable_with_rsasha256() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let name = Name::new().label("example").label("com");
+        let signer = Signer::new(key.clone(), Algorithm::RSASHA256, name, Duration::from_secs(60), true);
+
+        let tbs = b"this is the data that gets signed";
+        let signature = signer.sign(tbs).expect("signing failed");
+
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &key).unwrap();
+        verifier.update(tbs).unwrap();
+        assert!(verifier.finish(&signature).unwrap());
+    }
+
+    #[test]
+    fn test_sign_produces_a_signature_verifiable_with_ed25519() {
+        let key = PKey::generate_ed25519().unwrap();
+        let name = Name::new().label("example").label("com");
+        let signer = Signer::new(key.clone(), Algorithm::ED25519, name, Duration::from_secs(60), true);
+
+        let tbs = b"this is the data that gets signed";
+        let signature = signer.sign(tbs).expect("signing failed");
+
+        let mut verifier = Verifier::new_without_digest(&key).unwrap();
+        assert!(verifier.verify_oneshot(&signature, tbs).unwrap());
+    }
+}
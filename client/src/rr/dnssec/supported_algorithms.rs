@@ -0,0 +1,288 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bitsets of the DNSSEC algorithm/digest-type numbers a party supports, as advertised in an
+//! EDNS DAU/DHU option (RFC 6975). There is no N3U (NSEC3 hash understood) support here, since
+//! this tree has no NSEC3 implementation for it to apply to.
+
+use ::rr::dnssec::Algorithm;
+
+/// Walks an OPT record's RDATA -- a sequence of
+/// `OPTION-CODE (u16) OPTION-LENGTH (u16) OPTION-DATA`
+/// ([RFC 6891, section 6.1.2](https://tools.ietf.org/html/rfc6891#section-6.1.2)) -- and returns
+/// the concatenated option data of every option matching `option_code`. A malformed trailing
+/// option (one that claims more data than remains) stops the scan rather than erroring, since
+/// whatever was already found is still valid to use.
+fn edns_option_bytes(rdata: &[u8], option_code: u16) -> Vec<u8> {
+    let mut collected = Vec::new();
+    let mut rdata = rdata;
+
+    while rdata.len() >= 4 {
+        let code = ((rdata[0] as u16) << 8) | (rdata[1] as u16);
+        let option_length = (((rdata[2] as u16) << 8) | (rdata[3] as u16)) as usize;
+        rdata = &rdata[4..];
+
+        if option_length > rdata.len() {
+            break;
+        }
+
+        if code == option_code {
+            collected.extend_from_slice(&rdata[..option_length]);
+        }
+
+        rdata = &rdata[option_length..];
+    }
+
+    collected
+}
+
+/// A compact bitset over `Algorithm`, used to track which algorithms a zone's keys use
+/// and which ones a querying resolver has said it understands.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct SupportedAlgorithms {
+    bit_map: u8,
+}
+
+impl SupportedAlgorithms {
+    /// Returns a new, empty set
+    pub fn new() -> Self {
+        SupportedAlgorithms { bit_map: 0 }
+    }
+
+    /// Maps an `Algorithm` to its bit in `bit_map`, assigned explicitly per variant so that a
+    /// future addition to `Algorithm` can't silently collide with an existing bit the way
+    /// folding the raw IANA number through `% 8` could.
+    fn bit_for(algorithm: Algorithm) -> u8 {
+        match algorithm {
+            Algorithm::RSASHA256 => 1 << 0,
+            Algorithm::ECDSAP256SHA256 => 1 << 1,
+            Algorithm::ECDSAP384SHA384 => 1 << 2,
+            Algorithm::ED25519 => 1 << 3,
+        }
+    }
+
+    /// Adds `algorithm` to the set
+    pub fn set(&mut self, algorithm: Algorithm) {
+        self.bit_map |= Self::bit_for(algorithm);
+    }
+
+    /// Builds a set from the raw algorithm numbers of an EDNS DAU option's data,
+    /// skipping any the local build doesn't know about rather than failing the whole option.
+    pub fn from_u8s(algorithm_numbers: &[u8]) -> Self {
+        let mut supported = Self::new();
+        for &number in algorithm_numbers {
+            if let Ok(algorithm) = Algorithm::from_u8(number) {
+                supported.set(algorithm);
+            }
+        }
+        supported
+    }
+
+    /// The EDNS option code for the DNSSEC Algorithm Understood (DAU) option,
+    /// [RFC 6975, section 3](https://tools.ietf.org/html/rfc6975#section-3).
+    const DAU_OPTION_CODE: u16 = 5;
+
+    /// Builds a set from the DAU option of an OPT record's RDATA, as advertised in a query's
+    /// EDNS pseudo-RR.
+    ///
+    /// NOTE: this is the parsing primitive only, not a wired-up feature. There is no
+    /// `Message`/OPT record type anywhere in this tree to parse an incoming query into in the
+    /// first place (confirmed: nothing under `client/src` or `server/src` defines one), so
+    /// nothing calls this from real query handling yet -- every `Authority::lookup` caller
+    /// today passes a `SupportedAlgorithms` it built by hand. Wiring this in is blocked on that
+    /// `Message`/OPT type existing; it isn't a gap this function can close on its own.
+    pub fn from_edns_options(rdata: &[u8]) -> Self {
+        Self::from_u8s(&edns_option_bytes(rdata, Self::DAU_OPTION_CODE))
+    }
+
+    /// True if `algorithm` is a member of the set
+    pub fn has(&self, algorithm: Algorithm) -> bool {
+        self.bit_map & Self::bit_for(algorithm) != 0
+    }
+
+    /// True if no algorithms are set
+    pub fn is_empty(&self) -> bool {
+        self.bit_map == 0
+    }
+
+    /// The algorithms present in both `self` and `other`
+    pub fn intersection(&self, other: &Self) -> Self {
+        SupportedAlgorithms { bit_map: self.bit_map & other.bit_map }
+    }
+}
+
+impl Default for SupportedAlgorithms {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A compact bitset over DS digest-type numbers (e.g. 1 for SHA-1, 2 for SHA-256), as advertised
+/// in an EDNS DHU option. This is a distinct type from `SupportedAlgorithms` because DS digest
+/// types and DNSSEC signing algorithms are different IANA registries -- a digest type number and
+/// an `Algorithm` number with the same value mean different things.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct SupportedDigests {
+    bit_map: u64,
+}
+
+impl SupportedDigests {
+    /// Returns a new, empty set
+    pub fn new() -> Self {
+        SupportedDigests { bit_map: 0 }
+    }
+
+    /// Every digest-type number currently assigned by IANA (1: SHA-1, 2: SHA-256, 3: GOST
+    /// R 34.11-94, 4: SHA-384) fits in the low 64 values, so a 64-bit map gives each of them
+    /// its own bit; only a digest type 64 or above -- none exists today -- would alias another.
+    fn bit_for(digest_type: u8) -> u64 {
+        1 << (digest_type as u64 % 64)
+    }
+
+    /// Adds `digest_type` to the set
+    pub fn set(&mut self, digest_type: u8) {
+        self.bit_map |= Self::bit_for(digest_type);
+    }
+
+    /// Builds a set from the raw digest-type numbers of an EDNS DHU option's data, skipping none
+    /// of them -- unlike `SupportedAlgorithms::from_u8s`, every digest-type number is valid,
+    /// there's no enum of known ones to fail to recognize.
+    pub fn from_u8s(digest_types: &[u8]) -> Self {
+        let mut supported = Self::new();
+        for &digest_type in digest_types {
+            supported.set(digest_type);
+        }
+        supported
+    }
+
+    /// The EDNS option code for the Digest Algorithm Understood (DHU) option,
+    /// [RFC 6975, section 3](https://tools.ietf.org/html/rfc6975#section-3).
+    const DHU_OPTION_CODE: u16 = 6;
+
+    /// Builds a set from the DHU option of an OPT record's RDATA, as advertised in a query's
+    /// EDNS pseudo-RR. See `SupportedAlgorithms::from_edns_options` for the caveats shared with
+    /// this method: it's an unwired parsing primitive, not a feature, since this tree has no
+    /// `Message`/OPT type for any caller to have parsed a real query into.
+    pub fn from_edns_options(rdata: &[u8]) -> Self {
+        Self::from_u8s(&edns_option_bytes(rdata, Self::DHU_OPTION_CODE))
+    }
+
+    /// True if `digest_type` is a member of the set
+    pub fn has(&self, digest_type: u8) -> bool {
+        self.bit_map & Self::bit_for(digest_type) != 0
+    }
+
+    /// True if no digest types are set
+    pub fn is_empty(&self) -> bool {
+        self.bit_map == 0
+    }
+}
+
+impl Default for SupportedDigests {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use super::*;
+    use ::rr::dnssec::Algorithm;
+
+    #[test]
+    fn test_set_has() {
+        let mut supported = SupportedAlgorithms::new();
+        assert!(supported.is_empty());
+
+        supported.set(Algorithm::ECDSAP256SHA256);
+        assert!(supported.has(Algorithm::ECDSAP256SHA256));
+        assert!(!supported.has(Algorithm::RSASHA256));
+    }
+
+    #[test]
+    fn test_from_u8s_skips_unknown() {
+        let supported = SupportedAlgorithms::from_u8s(&[8, 200, 15]);
+
+        assert!(supported.has(Algorithm::RSASHA256));
+        assert!(supported.has(Algorithm::ED25519));
+        assert!(!supported.has(Algorithm::ECDSAP256SHA256));
+    }
+
+    #[test]
+    fn test_from_edns_options_reads_dau_option() {
+        // OPTION-CODE 5 (DAU), OPTION-LENGTH 2, algorithm numbers 8 (RSASHA256) and 15 (ED25519)
+        let rdata = [0x00, 0x05, 0x00, 0x02, 8, 15];
+        let supported = SupportedAlgorithms::from_edns_options(&rdata);
+
+        assert!(supported.has(Algorithm::RSASHA256));
+        assert!(supported.has(Algorithm::ED25519));
+        assert!(!supported.has(Algorithm::ECDSAP256SHA256));
+    }
+
+    #[test]
+    fn test_from_edns_options_ignores_other_options() {
+        // OPTION-CODE 3 (NSID), OPTION-LENGTH 1, then a DAU option for RSASHA256
+        let rdata = [0x00, 0x03, 0x00, 0x01, 0xAB, 0x00, 0x05, 0x00, 0x01, 8];
+        let supported = SupportedAlgorithms::from_edns_options(&rdata);
+
+        assert!(supported.has(Algorithm::RSASHA256));
+        assert!(!supported.has(Algorithm::ED25519));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let mut a = SupportedAlgorithms::new();
+        a.set(Algorithm::RSASHA256);
+        a.set(Algorithm::ED25519);
+
+        let mut b = SupportedAlgorithms::new();
+        b.set(Algorithm::ED25519);
+
+        let both = a.intersection(&b);
+        assert!(both.has(Algorithm::ED25519));
+        assert!(!both.has(Algorithm::RSASHA256));
+    }
+
+    #[test]
+    fn test_supported_digests_set_has() {
+        let mut supported = SupportedDigests::new();
+        assert!(supported.is_empty());
+
+        supported.set(2); // SHA-256
+        assert!(supported.has(2));
+        assert!(!supported.has(1));
+    }
+
+    #[test]
+    fn test_supported_digests_from_edns_options_reads_dhu_option() {
+        // OPTION-CODE 6 (DHU), OPTION-LENGTH 2, digest types 1 (SHA-1) and 2 (SHA-256)
+        let rdata = [0x00, 0x06, 0x00, 0x02, 1, 2];
+        let supported = SupportedDigests::from_edns_options(&rdata);
+
+        assert!(supported.has(1));
+        assert!(supported.has(2));
+        assert!(!supported.has(4));
+    }
+
+    #[test]
+    fn test_supported_digests_from_edns_options_ignores_dau_option() {
+        // a DAU option shouldn't be mistaken for a DHU option just because they share a format
+        let rdata = [0x00, 0x05, 0x00, 0x01, 8];
+        let supported = SupportedDigests::from_edns_options(&rdata);
+
+        assert!(supported.is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! domain name, the owner name of a resource record
+//!
+//! [RFC 1035, DOMAIN NAMES - IMPLEMENTATION AND SPECIFICATION, November 1987](https://tools.ietf.org/html/rfc1035)
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ::serialize::txt::*;
+use ::serialize::binary::*;
+use ::error::*;
+
+/// A domain name, stored as an ordered list of labels from most- to least-specific, e.g.
+/// `www.example.com.` is `["www", "example", "com"]`. Labels are kept lowercased, since DNS
+/// names are case-insensitive and canonical forms (required for DNSSEC) demand it.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Name {
+    labels: Vec<String>,
+}
+
+impl Name {
+    /// Creates a new, empty (root) name
+    pub fn new() -> Self {
+        Name { labels: Vec::new() }
+    }
+
+    /// Creates a name from labels that are already split out, e.g. for `www.example.com.`,
+    /// `vec!["www", "example", "com"]`
+    pub fn with_labels<I, S>(labels: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        Name { labels: labels.into_iter().map(|l| l.into().to_lowercase()).collect() }
+    }
+
+    /// Appends `label` as the next, less-specific component of this name and returns it,
+    /// e.g. `Name::new().label("www").label("example").label("com")` builds `www.example.com.`
+    pub fn label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_lowercase());
+        self
+    }
+
+    /// The number of labels in this name
+    pub fn num_labels(&self) -> u8 {
+        self.labels.len() as u8
+    }
+
+    /// Iterates over the labels, most- to least-specific
+    pub fn iter(&self) -> ::std::slice::Iter<String> {
+        self.labels.iter()
+    }
+
+    /// This name with every label lowercased; since labels are already stored lowercased this
+    /// is mostly a clone, but it's the canonical form DNSSEC signing requires regardless of how
+    /// a `Name` was built.
+    pub fn to_lowercase(&self) -> Self {
+        Name { labels: self.labels.iter().map(|l| l.to_lowercase()).collect() }
+    }
+
+    /// Parses a domain name out of master-file tokens, qualifying a relative name with
+    /// `origin` and handling the `@` shorthand for "the current origin".
+    pub fn parse(tokens: &Vec<Token>, origin: Option<&Name>) -> ParseResult<Self> {
+        let mut name_str = String::new();
+        for token in tokens {
+            match *token {
+                Token::CharData(ref s) => name_str.push_str(s),
+                ref t => return Err(ParseErrorKind::UnexpectedToken(t.clone()).into()),
+            }
+        }
+
+        Ok(Self::parse_str(&name_str, origin))
+    }
+
+    fn parse_str(s: &str, origin: Option<&Name>) -> Self {
+        if s == "@" {
+            return origin.cloned().unwrap_or_else(Name::new);
+        }
+
+        let mut labels = Vec::new();
+        let mut current = String::new();
+        let mut chars = s.chars().peekable();
+        let mut trailing_dot = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                    }
+                }
+                '.' => {
+                    labels.push(current.to_lowercase());
+                    current.clear();
+                    trailing_dot = chars.peek().is_none();
+                }
+                _ => current.push(c),
+            }
+        }
+        if !trailing_dot {
+            labels.push(current.to_lowercase());
+        }
+
+        let mut name = Name { labels: labels };
+
+        if !trailing_dot {
+            if let Some(origin) = origin {
+                name.labels.extend(origin.labels.iter().cloned());
+            }
+        }
+
+        name
+    }
+
+    /// Reads a name off the wire: a sequence of length-prefixed labels terminated by a
+    /// zero-length label.
+    pub fn read(decoder: &mut BinDecoder) -> DecodeResult<Self> {
+        let mut labels = Vec::new();
+
+        loop {
+            let length = try!(decoder.pop());
+            if length == 0 {
+                break;
+            }
+
+            let label_bytes = try!(decoder.read_vec(length as usize));
+            let label = try!(String::from_utf8(label_bytes)
+                .map_err(|e| DecodeErrorKind::Msg(format!("invalid label: {}", e))));
+            labels.push(label.to_lowercase());
+        }
+
+        Ok(Name { labels: labels })
+    }
+
+    /// Writes this name to the wire: one length-prefixed label per component, terminated by a
+    /// zero-length label.
+    pub fn emit(&self, encoder: &mut BinEncoder) -> EncodeResult {
+        for label in &self.labels {
+            let bytes = label.as_bytes();
+            try!(encoder.emit(bytes.len() as u8));
+            try!(encoder.emit_vec(bytes));
+        }
+        encoder.emit(0)
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Name {
+    /// Canonical DNSSEC name ordering, per
+    /// [RFC 4034, section 6.1](https://tools.ietf.org/html/rfc4034#section-6.1): compared
+    /// label by label starting from the least-specific (rightmost) label.
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        let self_labels: Vec<&String> = self.labels.iter().rev().collect();
+        let other_labels: Vec<&String> = other.labels.iter().rev().collect();
+        self_labels.cmp(&other_labels)
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for label in &self.labels {
+            try!(write!(f, "{}.", label));
+        }
+        if self.labels.is_empty() {
+            try!(write!(f, "."));
+        }
+        Ok(())
+    }
+}
+
+/// Builds the reverse-lookup name for `address`, e.g. `1.0.0.127.in-addr.arpa.` for
+/// `127.0.0.1`: the octets in reverse order under `in-addr.arpa`, per
+/// [RFC 1035, section 3.5](https://tools.ietf.org/html/rfc1035#section-3.5).
+impl From<Ipv4Addr> for Name {
+    fn from(addr: Ipv4Addr) -> Self {
+        let mut name = Name::new();
+        for octet in addr.octets().iter().rev() {
+            name = name.label(&octet.to_string());
+        }
+        name.label("in-addr").label("arpa")
+    }
+}
+
+/// Builds the reverse-lookup name for `address`, e.g. the 32 nibbles of the address in reverse
+/// order under `ip6.arpa`, per [RFC 3596, section 2.5](https://tools.ietf.org/html/rfc3596#section-2.5).
+impl From<Ipv6Addr> for Name {
+    fn from(addr: Ipv6Addr) -> Self {
+        let mut nibbles = Vec::with_capacity(32);
+        for segment in &addr.segments() {
+            nibbles.push((segment >> 12) & 0xF);
+            nibbles.push((segment >> 8) & 0xF);
+            nibbles.push((segment >> 4) & 0xF);
+            nibbles.push(segment & 0xF);
+        }
+
+        let mut name = Name::new();
+        for nibble in nibbles.iter().rev() {
+            name = name.label(&format!("{:x}", nibble));
+        }
+        name.label("ip6").label("arpa")
+    }
+}
+
+/// Builds the reverse-lookup name for `address`, dispatching to the IPv4 or IPv6 form
+impl From<IpAddr> for Name {
+    fn from(addr: IpAddr) -> Self {
+        match addr {
+            IpAddr::V4(v4) => Name::from(v4),
+            IpAddr::V6(v6) => Name::from(v6),
+        }
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_from_ipv4() {
+        let name = Name::from(Ipv4Addr::new(26, 3, 0, 103));
+        let expected = Name::new()
+            .label("103")
+            .label("0")
+            .label("3")
+            .label("26")
+            .label("in-addr")
+            .label("arpa");
+
+        assert_eq!(expected, name);
+    }
+
+    #[test]
+    fn test_from_ipv6() {
+        let name = Name::from(Ipv6Addr::from_str("4321:0:1:2:3:4:567:89ab").unwrap());
+        let expected_str = "b.a.9.8.7.6.5.0.4.0.0.0.3.0.0.0.2.0.0.0.1.0.0.0.0.0.0.0.1.2.3.4.ip6.arpa.";
+
+        assert_eq!(expected_str, name.to_string());
+    }
+
+    #[test]
+    fn test_from_ip_addr_dispatches() {
+        let v4 = Name::from(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(Name::from(Ipv4Addr::new(1, 2, 3, 4)), v4);
+    }
+
+    #[test]
+    fn test_canonical_ordering() {
+        let mut names = vec![Name::new().label("b").label("example").label("com"),
+                              Name::new().label("a").label("example").label("com"),
+                              Name::new().label("example").label("com")];
+        names.sort();
+
+        assert_eq!(names,
+                   vec![Name::new().label("example").label("com"),
+                        Name::new().label("a").label("example").label("com"),
+                        Name::new().label("b").label("example").label("com")]);
+    }
+}
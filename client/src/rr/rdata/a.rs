@@ -41,22 +41,62 @@
 //! ```
 
 use std::net::Ipv4Addr;
+use std::ops::Deref;
 
 use ::serialize::txt::*;
 use ::serialize::binary::*;
 use ::error::*;
 
+/// An IPv4 address, as carried in the RDATA of an `A` record.
+///
+/// This is a thin newtype rather than a bare `Ipv4Addr` so that `RData::A` can be matched
+/// against without also matching whatever else might one day hold an `Ipv4Addr`.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct A(Ipv4Addr);
+
+impl A {
+    /// Constructs a new `A` from four octets, e.g. `A::new(192, 0, 2, 1)`
+    pub fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        A(Ipv4Addr::new(a, b, c, d))
+    }
+
+    /// The underlying address
+    pub fn as_a(&self) -> &Ipv4Addr {
+        &self.0
+    }
+}
+
+impl From<Ipv4Addr> for A {
+    fn from(address: Ipv4Addr) -> Self {
+        A(address)
+    }
+}
+
+impl From<A> for Ipv4Addr {
+    fn from(a: A) -> Self {
+        a.0
+    }
+}
+
+impl Deref for A {
+    type Target = Ipv4Addr;
+
+    fn deref(&self) -> &Ipv4Addr {
+        &self.0
+    }
+}
+
 /// Read the RData from the given Decoder
-pub fn read(decoder: &mut BinDecoder) -> DecodeResult<Ipv4Addr> {
-    Ok(Ipv4Addr::new(try!(decoder.pop()),
-                     try!(decoder.pop()),
-                     try!(decoder.pop()),
-                     try!(decoder.pop())))
+pub fn read(decoder: &mut BinDecoder) -> DecodeResult<A> {
+    Ok(A::new(try!(decoder.pop()),
+              try!(decoder.pop()),
+              try!(decoder.pop()),
+              try!(decoder.pop())))
 }
 
 /// Write the RData from the given Decoder
-pub fn emit(encoder: &mut BinEncoder, address: &Ipv4Addr) -> EncodeResult {
-    let segments = address.octets();
+pub fn emit(encoder: &mut BinEncoder, address: &A) -> EncodeResult {
+    let segments = address.as_a().octets();
 
     try!(encoder.emit(segments[0]));
     try!(encoder.emit(segments[1]));
@@ -66,7 +106,7 @@ pub fn emit(encoder: &mut BinEncoder, address: &Ipv4Addr) -> EncodeResult {
 }
 
 /// Parse the RData from a set of Tokens
-pub fn parse(tokens: &Vec<Token>) -> ParseResult<Ipv4Addr> {
+pub fn parse(tokens: &Vec<Token>) -> ParseResult<A> {
     let mut token = tokens.iter();
 
     let address: Ipv4Addr = try!(token.next()
@@ -76,25 +116,25 @@ pub fn parse(tokens: &Vec<Token>) -> ParseResult<Ipv4Addr> {
         } else {
             Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
         }));
-    Ok(address)
+    Ok(A::from(address))
 }
 
 #[cfg(test)]
 mod mytests {
-    use std::net::Ipv4Addr;
     use std::str::FromStr;
+    use std::net::Ipv4Addr;
 
     use super::*;
     use serialize::binary::bin_tests::{test_read_data_set, test_emit_data_set};
 
-    fn get_data() -> Vec<(Ipv4Addr, Vec<u8>)> {
-        vec![(Ipv4Addr::from_str("0.0.0.0").unwrap(), vec![0, 0, 0, 0]), // base case
-             (Ipv4Addr::from_str("1.0.0.0").unwrap(), vec![1, 0, 0, 0]),
-             (Ipv4Addr::from_str("0.1.0.0").unwrap(), vec![0, 1, 0, 0]),
-             (Ipv4Addr::from_str("0.0.1.0").unwrap(), vec![0, 0, 1, 0]),
-             (Ipv4Addr::from_str("0.0.0.1").unwrap(), vec![0, 0, 0, 1]),
-             (Ipv4Addr::from_str("127.0.0.1").unwrap(), vec![127, 0, 0, 1]),
-             (Ipv4Addr::from_str("192.168.64.32").unwrap(), vec![192, 168, 64, 32])]
+    fn get_data() -> Vec<(A, Vec<u8>)> {
+        vec![(A::from(Ipv4Addr::from_str("0.0.0.0").unwrap()), vec![0, 0, 0, 0]), // base case
+             (A::from(Ipv4Addr::from_str("1.0.0.0").unwrap()), vec![1, 0, 0, 0]),
+             (A::from(Ipv4Addr::from_str("0.1.0.0").unwrap()), vec![0, 1, 0, 0]),
+             (A::from(Ipv4Addr::from_str("0.0.1.0").unwrap()), vec![0, 0, 1, 0]),
+             (A::from(Ipv4Addr::from_str("0.0.0.1").unwrap()), vec![0, 0, 0, 1]),
+             (A::from(Ipv4Addr::from_str("127.0.0.1").unwrap()), vec![127, 0, 0, 1]),
+             (A::from(Ipv4Addr::from_str("192.168.64.32").unwrap()), vec![192, 168, 64, 32])]
     }
 
     #[test]
@@ -106,4 +146,10 @@ mod mytests {
     fn test_write_to() {
         test_emit_data_set(get_data(), |ref mut e, d| emit(e, &d));
     }
+
+    #[test]
+    fn test_new_and_as_a() {
+        let a = A::new(26, 3, 0, 103);
+        assert_eq!(a.as_a(), &Ipv4Addr::new(26, 3, 0, 103));
+    }
 }
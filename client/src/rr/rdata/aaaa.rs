@@ -0,0 +1,142 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! IPv6 address record data
+//!
+//! [RFC 3596, DNS Extensions to Support IP Version 6, October 2003](https://tools.ietf.org/html/rfc3596)
+//!
+//! ```text
+//! 2.2 AAAA data format
+//!
+//!    A 128 bit IPv6 address is encoded in the data portion of an AAAA
+//!    resource record in network byte order (high-order byte first).
+//! ```
+
+use std::net::Ipv6Addr;
+use std::ops::Deref;
+
+use ::serialize::txt::*;
+use ::serialize::binary::*;
+use ::error::*;
+
+/// An IPv6 address, as carried in the RDATA of an `AAAA` record.
+///
+/// Mirrors `A`: a thin newtype over `Ipv6Addr` so `RData::AAAA` stays distinct from any other
+/// field that might hold one.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct AAAA(Ipv6Addr);
+
+impl AAAA {
+    /// Constructs a new `AAAA` from eight 16 bit segments
+    #[allow(too_many_arguments)]
+    pub fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16, g: u16, h: u16) -> Self {
+        AAAA(Ipv6Addr::new(a, b, c, d, e, f, g, h))
+    }
+
+    /// The underlying address
+    pub fn as_aaaa(&self) -> &Ipv6Addr {
+        &self.0
+    }
+}
+
+impl From<Ipv6Addr> for AAAA {
+    fn from(address: Ipv6Addr) -> Self {
+        AAAA(address)
+    }
+}
+
+impl From<AAAA> for Ipv6Addr {
+    fn from(aaaa: AAAA) -> Self {
+        aaaa.0
+    }
+}
+
+impl Deref for AAAA {
+    type Target = Ipv6Addr;
+
+    fn deref(&self) -> &Ipv6Addr {
+        &self.0
+    }
+}
+
+/// Read the RData from the given Decoder
+pub fn read(decoder: &mut BinDecoder) -> DecodeResult<AAAA> {
+    let segments = [try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16()),
+                     try!(decoder.read_u16())];
+
+    Ok(AAAA::new(segments[0],
+                 segments[1],
+                 segments[2],
+                 segments[3],
+                 segments[4],
+                 segments[5],
+                 segments[6],
+                 segments[7]))
+}
+
+/// Write the RData from the given Decoder
+pub fn emit(encoder: &mut BinEncoder, address: &AAAA) -> EncodeResult {
+    for segment in &address.as_aaaa().segments() {
+        try!(encoder.emit_u16(*segment));
+    }
+    Ok(())
+}
+
+/// Parse the RData from a set of Tokens
+pub fn parse(tokens: &Vec<Token>) -> ParseResult<AAAA> {
+    let mut token = tokens.iter();
+
+    let address: Ipv6Addr = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("ipv6 address".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            Ok(try!(s.parse()))
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+    Ok(AAAA::from(address))
+}
+
+#[cfg(test)]
+mod mytests {
+    use std::str::FromStr;
+    use std::net::Ipv6Addr;
+
+    use super::*;
+    use serialize::binary::bin_tests::{test_read_data_set, test_emit_data_set};
+
+    fn get_data() -> Vec<(AAAA, Vec<u8>)> {
+        vec![(AAAA::from(Ipv6Addr::from_str("::").unwrap()), vec![0; 16]),
+             (AAAA::from(Ipv6Addr::from_str("4321:0:1:2:3:4:567:89ab").unwrap()),
+              vec![0x43, 0x21, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x05,
+                   0x67, 0x89, 0xab])]
+    }
+
+    #[test]
+    fn test_parse() {
+        test_read_data_set(get_data(), |ref mut d| read(d));
+    }
+
+    #[test]
+    fn test_write_to() {
+        test_emit_data_set(get_data(), |ref mut e, d| emit(e, &d));
+    }
+}
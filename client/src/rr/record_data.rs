@@ -0,0 +1,186 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! record data enum variants, i.e. the `RDATA` portion of a resource record
+
+use ::rr::{Name, RecordType};
+use ::rr::rdata::{a, aaaa, A, AAAA, MX, SOA, SRV, TXT};
+use ::rr::dnssec::rdata::{self, DNSKEY, DS, NSEC, SIG};
+use ::serialize::binary::*;
+use ::serialize::txt::*;
+use ::error::*;
+
+/// The data portion of a resource record, tagged by its own `RecordType`.
+///
+/// `RData`'s variant *is* the authoritative record type: `Record::rr_type()` reads it straight
+/// off of here rather than trusting a separately stored field, so a record can never end up
+/// with RDATA that doesn't match its declared type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RData {
+    /// An IPv4 address, see [RFC 1035, section 3.4.1](https://tools.ietf.org/html/rfc1035)
+    A(A),
+    /// An IPv6 address, see [RFC 3596](https://tools.ietf.org/html/rfc3596)
+    AAAA(AAAA),
+    /// A canonical name alias, see [RFC 1035, section 3.3.1](https://tools.ietf.org/html/rfc1035)
+    CNAME(Name),
+    /// A delegation signer, see [RFC 4034, section 5](https://tools.ietf.org/html/rfc4034)
+    DS(DS),
+    /// A zone's public signing key, see [RFC 4034, section 2](https://tools.ietf.org/html/rfc4034)
+    DNSKEY(DNSKEY),
+    /// A mail exchange, see [RFC 1035, section 3.3.9](https://tools.ietf.org/html/rfc1035)
+    MX(MX),
+    /// An authoritative name server, see [RFC 1035, section 3.3.11](https://tools.ietf.org/html/rfc1035)
+    NS(Name),
+    /// Next Secure, authenticated denial of existence, see
+    /// [RFC 4034, section 4](https://tools.ietf.org/html/rfc4034)
+    NSEC(NSEC),
+    /// A domain name pointer, see [RFC 1035, section 3.3.12](https://tools.ietf.org/html/rfc1035)
+    PTR(Name),
+    /// The start of a zone of authority, see [RFC 1035, section 3.3.13](https://tools.ietf.org/html/rfc1035)
+    SOA(SOA),
+    /// An RRSIG (or SIG(0)) signature, see [RFC 4034, section 3](https://tools.ietf.org/html/rfc4034)
+    SIG(SIG),
+    /// A service locator, see [RFC 2782](https://tools.ietf.org/html/rfc2782)
+    SRV(SRV),
+    /// Text strings, see [RFC 1035, section 3.3.14](https://tools.ietf.org/html/rfc1035)
+    TXT(TXT),
+    /// A dynamic-update record, see [RFC 2136, section 2.5](https://tools.ietf.org/html/rfc2136).
+    /// These always have RDLENGTH 0 on the wire; the `RecordType` they apply to travels with
+    /// them since, with empty RDATA, there's nothing else to decode a type out of. Keeping this
+    /// as a variant rather than making `Record::rdata` optional means every record, update or
+    /// not, always has well-formed RDATA to match on. `RData::read` also returns this variant
+    /// for a record type it has no decoder for, after skipping that record's RDATA; those
+    /// bytes are not kept.
+    Update(RecordType),
+}
+
+impl RData {
+    /// The `RecordType` that corresponds to this variant on the wire
+    pub fn to_record_type(&self) -> RecordType {
+        match *self {
+            RData::A(..) => RecordType::A,
+            RData::AAAA(..) => RecordType::AAAA,
+            RData::CNAME(..) => RecordType::CNAME,
+            RData::DS(..) => RecordType::DS,
+            RData::DNSKEY(..) => RecordType::DNSKEY,
+            RData::MX(..) => RecordType::MX,
+            RData::NS(..) => RecordType::NS,
+            RData::NSEC(..) => RecordType::NSEC,
+            RData::PTR(..) => RecordType::PTR,
+            RData::SOA(..) => RecordType::SOA,
+            RData::SIG(..) => RecordType::RRSIG,
+            RData::SRV(..) => RecordType::SRV,
+            RData::TXT(..) => RecordType::TXT,
+            RData::Update(record_type) => record_type,
+        }
+    }
+
+    /// Reads `rdata_length` bytes of RDATA for `record_type` off of `decoder`
+    pub fn read(decoder: &mut BinDecoder,
+                record_type: RecordType,
+                rdata_length: u16)
+                -> DecodeResult<Self> {
+        if rdata_length == 0 {
+            return Ok(RData::Update(record_type));
+        }
+
+        Ok(match record_type {
+            RecordType::A => RData::A(try!(a::read(decoder))),
+            RecordType::AAAA => RData::AAAA(try!(aaaa::read(decoder))),
+            RecordType::CNAME => RData::CNAME(try!(Name::read(decoder))),
+            RecordType::DS => RData::DS(try!(rdata::ds::read(decoder, rdata_length))),
+            RecordType::DNSKEY => RData::DNSKEY(try!(rdata::dnskey::read(decoder, rdata_length))),
+            RecordType::MX => RData::MX(try!(MX::read(decoder))),
+            RecordType::NS => RData::NS(try!(Name::read(decoder))),
+            RecordType::NSEC => RData::NSEC(try!(rdata::nsec::read(decoder, rdata_length))),
+            RecordType::PTR => RData::PTR(try!(Name::read(decoder))),
+            RecordType::SOA => RData::SOA(try!(SOA::read(decoder))),
+            RecordType::RRSIG => RData::SIG(try!(rdata::sig::read(decoder, rdata_length))),
+            RecordType::SRV => RData::SRV(try!(SRV::read(decoder))),
+            RecordType::TXT => RData::TXT(try!(TXT::read(decoder, rdata_length))),
+            _ => {
+                // An unsupported type still has to have its RDATA consumed off of `decoder`,
+                // or every record after this one in the same message desyncs and misparses.
+                try!(decoder.read_vec(rdata_length as usize));
+                RData::Update(record_type)
+            }
+        })
+    }
+
+    /// Writes this RDATA to `encoder` in wire form; `Update` writes nothing, matching its
+    /// RDLENGTH 0 on the wire.
+    pub fn emit(&self, encoder: &mut BinEncoder) -> EncodeResult {
+        match *self {
+            RData::A(ref address) => a::emit(encoder, address),
+            RData::AAAA(ref address) => aaaa::emit(encoder, address),
+            RData::CNAME(ref name) => name.emit(encoder),
+            RData::DS(ref ds) => rdata::ds::emit(encoder, ds),
+            RData::DNSKEY(ref dnskey) => rdata::dnskey::emit(encoder, dnskey),
+            RData::MX(ref mx) => mx.emit(encoder),
+            RData::NS(ref name) => name.emit(encoder),
+            RData::NSEC(ref nsec) => rdata::nsec::emit(encoder, nsec),
+            RData::PTR(ref name) => name.emit(encoder),
+            RData::SOA(ref soa) => soa.emit(encoder),
+            RData::SIG(ref sig) => rdata::sig::emit(encoder, sig),
+            RData::SRV(ref srv) => srv.emit(encoder),
+            RData::TXT(ref txt) => txt.emit(encoder),
+            RData::Update(..) => Ok(()),
+        }
+    }
+
+    /// Parses the master-file token form of RDATA for `record_type`
+    pub fn parse(record_type: RecordType,
+                 tokens: &Vec<Token>,
+                 origin: Option<&Name>)
+                 -> ParseResult<Self> {
+        Ok(match record_type {
+            RecordType::A => RData::A(try!(a::parse(tokens))),
+            RecordType::AAAA => RData::AAAA(try!(aaaa::parse(tokens))),
+            RecordType::CNAME => RData::CNAME(try!(Name::parse(tokens, origin))),
+            RecordType::MX => RData::MX(try!(MX::parse(tokens, origin))),
+            RecordType::NS => RData::NS(try!(Name::parse(tokens, origin))),
+            RecordType::PTR => RData::PTR(try!(Name::parse(tokens, origin))),
+            RecordType::SOA => RData::SOA(try!(SOA::parse(tokens, origin))),
+            RecordType::SRV => RData::SRV(try!(SRV::parse(tokens, origin))),
+            RecordType::TXT => RData::TXT(try!(TXT::parse(tokens))),
+            _ => return Err(ParseErrorKind::UnsupportedRecordType(record_type).into()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use ::rr::{RecordType, RData};
+    use ::rr::rdata::A;
+    use ::serialize::binary::*;
+
+    #[test]
+    fn test_read_of_unsupported_type_does_not_desync_the_decoder() {
+        // An RData::read for an unsupported type (here, HINFO) still has to consume its
+        // rdata_length bytes, or the A record that follows it in the same message would be
+        // misread starting from the wrong offset.
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[0xAB, 0xCD, 0xEF]); // three bytes of unsupported HINFO RDATA
+        bytes.extend_from_slice(&[26, 3, 0, 103]); // a trailing A record's RDATA
+
+        let mut decoder = BinDecoder::new(&bytes);
+        let unsupported = RData::read(&mut decoder, RecordType::HINFO, 3).expect("failed to read HINFO");
+        assert_eq!(unsupported, RData::Update(RecordType::HINFO));
+
+        let a = RData::read(&mut decoder, RecordType::A, 4).expect("failed to read A");
+        assert_eq!(a, RData::A(A::new(26, 3, 0, 103)));
+    }
+}
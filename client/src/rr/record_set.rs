@@ -0,0 +1,175 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! record_set is the collection of resource records for a given name and record type
+
+use ::rr::{Name, Record, RecordType, RData};
+
+/// A set of resource records sharing the same `Name` and `RecordType`, plus
+/// the RRSIGs covering that set (if the zone is signed).
+///
+/// This mirrors the "RRset" concept from the DNS specs: the authoritative
+/// records and their signatures are kept separate so that re-signing never
+/// has to pick the signatures back out of the authoritative data.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordSet {
+    name: Name,
+    record_type: RecordType,
+    ttl: u32,
+    records: Vec<Record>,
+    rrsigs: Vec<Record>,
+    serial: u32,
+}
+
+impl RecordSet {
+    /// Creates a new, empty RecordSet for `name` and `record_type` with the given `ttl`.
+    pub fn new(name: Name, record_type: RecordType, ttl: u32) -> Self {
+        RecordSet {
+            name: name,
+            record_type: record_type,
+            ttl: ttl,
+            records: Vec::new(),
+            rrsigs: Vec::new(),
+            serial: 0,
+        }
+    }
+
+    /// The name all records in this set share
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// The type all records in this set share
+    pub fn record_type(&self) -> RecordType {
+        self.record_type
+    }
+
+    /// The ttl to be used for all records in this set
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// Inserts `record` into the set, recording the serial number of the update that added it.
+    pub fn insert(&mut self, record: Record, serial: u32) -> bool {
+        if self.records.contains(&record) {
+            return false;
+        }
+
+        self.serial = serial;
+        self.records.push(record);
+        true
+    }
+
+    /// All of the authoritative records in this set, i.e. everything but the RRSIGs
+    pub fn records_without_rrsigs(&self) -> ::std::slice::Iter<Record> {
+        self.records.iter()
+    }
+
+    /// Mutable access to the authoritative records, e.g. to bump an SOA serial in place
+    pub fn records_without_rrsigs_mut(&mut self) -> ::std::slice::IterMut<Record> {
+        self.records.iter_mut()
+    }
+
+    /// The RRSIG records currently covering this set
+    pub fn rrsigs(&self) -> &[Record] {
+        &self.rrsigs
+    }
+
+    /// Adds a freshly computed RRSIG record, replacing any previous signature from the same signer
+    /// (the same signer name, key tag and algorithm).
+    pub fn insert_rrsig(&mut self, rrsig: Record) {
+        let signer = match *rrsig.rdata() {
+            RData::SIG(ref sig) => Some((sig.signer_name().clone(), sig.key_tag(), sig.algorithm())),
+            _ => None,
+        };
+
+        if let Some((signer_name, key_tag, algorithm)) = signer {
+            self.rrsigs.retain(|existing| match *existing.rdata() {
+                RData::SIG(ref sig) => {
+                    *sig.signer_name() != signer_name || sig.key_tag() != key_tag ||
+                    sig.algorithm() != algorithm
+                }
+                _ => true,
+            });
+        }
+
+        self.rrsigs.push(rrsig);
+    }
+
+    /// Discards all RRSIGs, in preparation for a re-sign of the zone
+    pub fn clear_rrsigs(&mut self) {
+        self.rrsigs.clear();
+    }
+}
+
+#[cfg(test)]
+mod mytests {
+    use ::rr::{Name, Record, RecordType};
+    use ::rr::dnssec::Algorithm;
+    use ::rr::dnssec::rdata::SIG;
+
+    use super::*;
+
+    fn rrsig(signer_name: Name, sig: u8) -> Record {
+        let rdata = SIG::new(RecordType::A,
+                              Algorithm::RSASHA256,
+                              2,
+                              3600,
+                              4102444800,
+                              1577836800,
+                              0,
+                              signer_name,
+                              vec![sig]);
+        Record::from_rdata(Name::new().label("example").label("com"), 3600, RData::SIG(rdata))
+    }
+
+    #[test]
+    fn test_insert_rrsig_replaces_same_signer() {
+        let mut set = RecordSet::new(Name::new().label("example").label("com"), RecordType::A, 3600);
+        let signer = Name::new().label("example").label("com");
+
+        set.insert_rrsig(rrsig(signer.clone(), 1));
+        set.insert_rrsig(rrsig(signer, 2));
+
+        assert_eq!(set.rrsigs().len(), 1);
+        match *set.rrsigs()[0].rdata() {
+            RData::SIG(ref sig) => assert_eq!(sig.sig(), &[2]),
+            _ => panic!("expected a SIG"),
+        }
+    }
+
+    #[test]
+    fn test_insert_rrsig_keeps_distinct_signers() {
+        let mut set = RecordSet::new(Name::new().label("example").label("com"), RecordType::A, 3600);
+        let ksk = Name::new().label("ksk").label("example").label("com");
+        let zsk = Name::new().label("zsk").label("example").label("com");
+
+        set.insert_rrsig(rrsig(ksk, 1));
+        set.insert_rrsig(rrsig(zsk, 2));
+
+        assert_eq!(set.rrsigs().len(), 2);
+    }
+
+    #[test]
+    fn test_clear_rrsigs() {
+        let mut set = RecordSet::new(Name::new().label("example").label("com"), RecordType::A, 3600);
+        set.insert_rrsig(rrsig(Name::new().label("example").label("com"), 1));
+
+        set.clear_rrsigs();
+
+        assert!(set.rrsigs().is_empty());
+    }
+}
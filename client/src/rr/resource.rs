@@ -0,0 +1,149 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Resource record, a name, class, ttl, and type-tagged rdata payload
+//!
+//! [RFC 1035, DOMAIN NAMES - IMPLEMENTATION AND SPECIFICATION, November 1987](https://tools.ietf.org/html/rfc1035)
+//!
+//! ```text
+//! 4.1.3. Resource record format
+//!
+//! The answer, authority, and additional sections all share the same
+//! format: a variable number of resource records, where the number of
+//! records is specified in the corresponding count field in the header.
+//! ```
+
+use ::rr::{DNSClass, Name, RData, RecordType};
+use ::serialize::binary::*;
+use ::error::*;
+
+/// A resource record: an owner name, class, TTL, and RDATA payload.
+///
+/// There is deliberately no separately stored record type — `rr_type()` reads it off of
+/// `rdata`'s own discriminant, so a `Record` can never disagree with the data it carries.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Record {
+    name: Name,
+    dns_class: DNSClass,
+    ttl: u32,
+    rdata: RData,
+}
+
+impl Record {
+    /// Constructs a record from its `RData`; the record's type is derived from `rdata` and the
+    /// class defaults to `IN`, the overwhelmingly common case.
+    pub fn from_rdata(name: Name, ttl: u32, rdata: RData) -> Self {
+        Record {
+            name: name,
+            dns_class: DNSClass::IN,
+            ttl: ttl,
+            rdata: rdata,
+        }
+    }
+
+    /// The owner name of this record
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Sets the owner name of this record
+    pub fn set_name(&mut self, name: Name) -> &mut Self {
+        self.name = name;
+        self
+    }
+
+    /// The record type, derived from the `RData` this record carries
+    pub fn rr_type(&self) -> RecordType {
+        self.rdata.to_record_type()
+    }
+
+    /// Alias for `rr_type`
+    pub fn record_type(&self) -> RecordType {
+        self.rr_type()
+    }
+
+    /// The class of this record, almost always `IN`
+    pub fn dns_class(&self) -> DNSClass {
+        self.dns_class
+    }
+
+    /// Sets the class of this record
+    pub fn set_dns_class(&mut self, dns_class: DNSClass) -> &mut Self {
+        self.dns_class = dns_class;
+        self
+    }
+
+    /// The time-to-live of this record, in seconds
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// Sets the time-to-live of this record, in seconds
+    pub fn set_ttl(&mut self, ttl: u32) -> &mut Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The record's payload
+    pub fn rdata(&self) -> &RData {
+        &self.rdata
+    }
+
+    /// Mutable access to the record's payload, e.g. to bump an SOA serial in place
+    pub fn rdata_mut(&mut self) -> &mut RData {
+        &mut self.rdata
+    }
+
+    /// Replaces the record's payload; the record's type changes along with it, since it's
+    /// derived from `rdata`
+    pub fn set_rdata(&mut self, rdata: RData) -> &mut Self {
+        self.rdata = rdata;
+        self
+    }
+
+    /// Reads a full resource record (name, type, class, ttl, rdlength, rdata) off of `decoder`
+    pub fn read(decoder: &mut BinDecoder) -> DecodeResult<Self> {
+        let name = try!(Name::read(decoder));
+        let record_type = try!(RecordType::from_u16(try!(decoder.read_u16())));
+        let dns_class = try!(DNSClass::from_u16(try!(decoder.read_u16())));
+        let ttl = try!(decoder.read_u32());
+        let rdata_length = try!(decoder.read_u16());
+        let rdata = try!(RData::read(decoder, record_type, rdata_length));
+
+        Ok(Record {
+            name: name,
+            dns_class: dns_class,
+            ttl: ttl,
+            rdata: rdata,
+        })
+    }
+
+    /// Writes this record to `encoder` in wire form
+    pub fn emit(&self, encoder: &mut BinEncoder) -> EncodeResult {
+        try!(self.name.emit(encoder));
+        try!(encoder.emit_u16(self.rr_type().to_u16()));
+        try!(encoder.emit_u16(self.dns_class.to_u16()));
+        try!(encoder.emit_u32(self.ttl));
+
+        let mut rdata_buf = Vec::new();
+        {
+            let mut rdata_encoder = BinEncoder::new(&mut rdata_buf);
+            try!(self.rdata.emit(&mut rdata_encoder));
+        }
+        try!(encoder.emit_u16(rdata_buf.len() as u16));
+        encoder.emit_vec(&rdata_buf)
+    }
+}
@@ -0,0 +1,48 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! rr_key groups a name and record type, the key under which a zone's RRsets are stored
+
+use ::rr::{Name, RecordType};
+
+/// Groups a name and record type together, the key under which a zone stores an RRset. Lives
+/// alongside `RecordSet` rather than in the server crate's `Authority` because the zone file
+/// `Parser` (also in this crate) needs to build a `BTreeMap<RrKey, RecordSet>` directly.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub struct RrKey {
+    name: Name,
+    record_type: RecordType,
+}
+
+impl RrKey {
+    /// Creates a new key for the RRset at `name` of type `record_type`
+    pub fn new(name: Name, record_type: RecordType) -> Self {
+        RrKey {
+            name: name,
+            record_type: record_type,
+        }
+    }
+
+    /// The owner name of the RRset this key identifies
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// The record type of the RRset this key identifies
+    pub fn record_type(&self) -> RecordType {
+        self.record_type
+    }
+}
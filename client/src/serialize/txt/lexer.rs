@@ -0,0 +1,361 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! character-level scanning of master-file text (RFC 1035 §5) into `Token`s
+
+use ::error::*;
+use super::token::Token;
+
+/// Scans master-file text into a stream of `Token`s, one `next_token()` call at a time.
+///
+/// Parentheses are tracked here (so a record can be continued across lines) but are otherwise
+/// transparent to the parser: they never surface as tokens of their own, they just suppress the
+/// `Token::EOL` that a bare newline would otherwise produce. `;` starts a comment that runs to
+/// the end of the line; `"..."` is a quoted string; `\` escapes the next character. Inside a
+/// quoted string the backslash is dropped; in bare character-data it is kept together with the
+/// character it escapes, for the consumer to interpret (e.g. `Name::parse` reads `\.` as a
+/// literal dot within a label).
+///
+/// The master-file control directives (`$ORIGIN`, `$TTL`, `$INCLUDE`, `$GENERATE`) are
+/// recognized here, at the lexer level, since only the lexer knows whether a line began with
+/// whitespace (record continuation) or `$` (a directive). Everything after the directive's
+/// keyword is read as its own single-line token; the parser is the one that decides what a
+/// directive does.
+pub struct Lexer {
+    txt: Vec<char>,
+    idx: usize,
+    paren_depth: u8,
+    start_of_line: bool,
+}
+
+impl Lexer {
+    /// Creates a lexer over `txt`, the full contents of a zone file (or an `$INCLUDE`d
+    /// fragment of one)
+    pub fn new(txt: &str) -> Self {
+        Lexer {
+            txt: txt.chars().collect(),
+            idx: 0,
+            paren_depth: 0,
+            start_of_line: true,
+        }
+    }
+
+    /// Reads the next `Token`, or `None` at the end of input
+    pub fn next_token(&mut self) -> ParseResult<Option<Token>> {
+        loop {
+            let at_line_start = self.start_of_line;
+            if self.start_of_line {
+                if let Some(c) = self.peek() {
+                    if c == ' ' || c == '\t' {
+                        self.skip_whitespace();
+                        self.start_of_line = false;
+                        match self.peek() {
+                            Some(';') | Some('\n') | None => continue, // a blank-ish line
+                            _ => return Ok(Some(Token::Blank)),
+                        }
+                    }
+                }
+                self.start_of_line = false;
+            } else {
+                self.skip_whitespace();
+            }
+
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return Ok(None),
+            };
+
+            match c {
+                '\n' => {
+                    self.advance();
+                    if self.paren_depth > 0 {
+                        continue;
+                    }
+                    self.start_of_line = true;
+                    return Ok(Some(Token::EOL));
+                }
+                ';' => {
+                    self.skip_comment();
+                    continue;
+                }
+                '(' => {
+                    self.advance();
+                    self.paren_depth += 1;
+                    continue;
+                }
+                ')' => {
+                    self.advance();
+                    self.paren_depth = self.paren_depth.saturating_sub(1);
+                    continue;
+                }
+                '"' => {
+                    self.advance();
+                    return Ok(Some(Token::CharData(try!(self.read_quoted()))));
+                }
+                '$' if at_line_start => {
+                    return self.read_directive();
+                }
+                _ => {
+                    self.advance();
+                    return Ok(Some(Token::CharData(try!(self.read_char_data(c)))));
+                }
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.txt.get(self.idx).cloned()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.idx += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == ' ' || c == '\t' {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.advance();
+        }
+    }
+
+    /// reads a `"`-delimited string, the opening `"` already consumed
+    fn read_quoted(&mut self) -> ParseResult<String> {
+        let mut s = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => return Ok(s),
+                Some('\\') => {
+                    match self.advance() {
+                        Some(c) => s.push(c),
+                        None => return Err(ParseErrorKind::Msg("unterminated escape in quoted string".to_string()).into()),
+                    }
+                }
+                Some(c) => s.push(c),
+                None => return Err(ParseErrorKind::Msg("unterminated quoted string".to_string()).into()),
+            }
+        }
+    }
+
+    /// reads an unquoted run of character data, `first` already consumed
+    fn read_char_data(&mut self, first: char) -> ParseResult<String> {
+        let mut s = String::new();
+        s.push(first);
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | '(' | ')' | ';' => break,
+                '\\' => {
+                    self.advance();
+                    match self.advance() {
+                        Some(escaped) => {
+                            s.push('\\');
+                            s.push(escaped);
+                        }
+                        None => return Err(ParseErrorKind::Msg("unterminated escape".to_string()).into()),
+                    }
+                }
+                c => {
+                    self.advance();
+                    s.push(c);
+                }
+            }
+        }
+        Ok(s)
+    }
+
+    /// reads the next whitespace-delimited field of the current line, honoring quoting and
+    /// escapes but not parentheses (directives are always a single line); `None` at `;`, `\n`,
+    /// or end of input
+    fn read_field(&mut self) -> ParseResult<Option<String>> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('\n') | Some(';') => Ok(None),
+            Some('"') => {
+                self.advance();
+                Ok(Some(try!(self.read_quoted())))
+            }
+            Some(c) => {
+                self.advance();
+                Ok(Some(try!(self.read_char_data(c))))
+            }
+        }
+    }
+
+    /// reads the remaining whitespace-delimited fields of the current line
+    fn read_fields(&mut self) -> ParseResult<Vec<String>> {
+        let mut fields = Vec::new();
+        while let Some(field) = try!(self.read_field()) {
+            fields.push(field);
+        }
+        Ok(fields)
+    }
+
+    /// dispatches a `$`-directive line, the `$` itself not yet consumed
+    fn read_directive(&mut self) -> ParseResult<Option<Token>> {
+        self.advance(); // the '$'
+        let name = match try!(self.read_field()) {
+            Some(name) => name,
+            None => return Err(ParseErrorKind::Msg("expected a directive name after '$'".to_string()).into()),
+        };
+
+        self.start_of_line = false;
+
+        match name.to_uppercase().as_str() {
+            "ORIGIN" => {
+                let origin = try!(try!(self.read_field())
+                    .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("$ORIGIN requires a name".to_string()))));
+                Ok(Some(Token::Origin(origin)))
+            }
+            "TTL" => {
+                let ttl_str = try!(try!(self.read_field())
+                    .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("$TTL requires a value".to_string()))));
+                Ok(Some(Token::Ttl(try!(parse_ttl(&ttl_str)))))
+            }
+            "INCLUDE" => {
+                let mut fields = try!(self.read_fields());
+                if fields.is_empty() {
+                    return Err(ParseErrorKind::Msg("$INCLUDE requires a path".to_string()).into());
+                }
+                let path = fields.remove(0);
+                let origin = if fields.is_empty() { None } else { Some(fields.remove(0)) };
+                Ok(Some(Token::Include(path, origin)))
+            }
+            "GENERATE" => Ok(Some(Token::Generate(try!(self.read_fields())))),
+            other => Err(ParseErrorKind::Msg(format!("unknown master-file directive: ${}", other)).into()),
+        }
+    }
+}
+
+/// Parses a `$TTL`-style duration: a bare number of seconds, or BIND's `1s`/`2m`/`3h`/`4d`/`5w`
+/// unit suffixes (case-insensitive)
+fn parse_ttl(s: &str) -> ParseResult<u32> {
+    if let Ok(ttl) = s.parse::<u32>() {
+        return Ok(ttl);
+    }
+
+    if s.is_empty() {
+        return Err(ParseErrorKind::Msg(format!("invalid ttl: {}", s)).into());
+    }
+
+    let (digits, unit) = s.split_at(s.len() - 1);
+    let scale = match unit.to_uppercase().as_str() {
+        "S" => 1,
+        "M" => 60,
+        "H" => 60 * 60,
+        "D" => 24 * 60 * 60,
+        "W" => 7 * 24 * 60 * 60,
+        _ => return Err(ParseErrorKind::Msg(format!("invalid ttl: {}", s)).into()),
+    };
+
+    digits.parse::<u32>()
+        .map(|n| n * scale)
+        .map_err(|_| ParseErrorKind::Msg(format!("invalid ttl: {}", s)).into())
+}
+
+#[cfg(test)]
+mod mytests {
+    use super::*;
+
+    fn tokens(txt: &str) -> Vec<Token> {
+        let mut lexer = Lexer::new(txt);
+        let mut tokens = Vec::new();
+        while let Some(token) = lexer.next_token().unwrap() {
+            tokens.push(token);
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_origin_directive() {
+        assert_eq!(vec![Token::Origin("example.com.".to_string())], tokens("$ORIGIN example.com."));
+    }
+
+    #[test]
+    fn test_ttl_directive_accepts_bare_seconds_and_bind_units() {
+        assert_eq!(vec![Token::Ttl(3600)], tokens("$TTL 3600"));
+        assert_eq!(vec![Token::Ttl(3600)], tokens("$TTL 1h"));
+        assert_eq!(vec![Token::Ttl(2 * 24 * 60 * 60)], tokens("$TTL 2D"));
+    }
+
+    #[test]
+    fn test_ttl_directive_rejects_empty_string_instead_of_panicking() {
+        let mut lexer = Lexer::new("$TTL \"\"");
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn test_include_directive_with_and_without_origin() {
+        assert_eq!(vec![Token::Include("db.example".to_string(), None)],
+                   tokens("$INCLUDE db.example"));
+        assert_eq!(vec![Token::Include("db.example".to_string(), Some("sub.example.com.".to_string()))],
+                   tokens("$INCLUDE db.example sub.example.com."));
+    }
+
+    #[test]
+    fn test_generate_directive_splits_into_fields() {
+        assert_eq!(vec![Token::Generate(vec!["1-5".to_string(),
+                                              "host$".to_string(),
+                                              "A".to_string(),
+                                              "192.0.2.$".to_string()])],
+                   tokens("$GENERATE 1-5 host$ A 192.0.2.$"));
+    }
+
+    #[test]
+    fn test_parens_suppress_eol_across_lines() {
+        assert_eq!(vec![Token::CharData("a".to_string()),
+                         Token::CharData("b".to_string()),
+                         Token::CharData("c".to_string()),
+                         Token::EOL],
+                   tokens("(a\nb\nc)\n"));
+    }
+
+    #[test]
+    fn test_comment_runs_to_end_of_line() {
+        assert_eq!(vec![Token::CharData("a".to_string()), Token::EOL, Token::CharData("b".to_string())],
+                   tokens("a ; a trailing comment\nb"));
+    }
+
+    #[test]
+    fn test_quoted_string_with_escaped_quote() {
+        assert_eq!(vec![Token::CharData("say \"hi\"".to_string())], tokens("\"say \\\"hi\\\"\""));
+    }
+
+    #[test]
+    fn test_blank_owner_on_continuation_line() {
+        assert_eq!(vec![Token::CharData("a".to_string()),
+                         Token::CharData("A".to_string()),
+                         Token::EOL,
+                         Token::Blank,
+                         Token::CharData("NS".to_string()),
+                         Token::EOL],
+                   tokens("a A\n  NS\n"));
+    }
+}
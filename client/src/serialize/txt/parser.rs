@@ -0,0 +1,445 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! assembles the `Token`s a `Lexer` produces into a zone's `RrKey`-keyed `RecordSet`s
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+
+use ::rr::{DNSClass, Name, RData, Record, RecordSet, RecordType, RrKey};
+use ::error::*;
+
+use super::lexer::Lexer;
+use super::token::Token;
+
+/// Parses master-file text (RFC 1035 §5) into a zone's origin and its `RrKey`-keyed RRsets.
+///
+/// Records are the `Lexer`'s concern (owner/ttl/class/type/rdata, one `Token::EOL` per record);
+/// this is where that grammar is assembled and where the master-file control directives take
+/// effect: `$ORIGIN` and `$TTL` are parser state that subsequent records are qualified or
+/// defaulted against, `$INCLUDE` recurses into another file's lexer under the same state, and
+/// `$GENERATE` expands its range directly into records without ever being tokenized as one.
+pub struct Parser;
+
+impl Parser {
+    /// Creates a new, stateless parser; all state lives in a single `parse` call
+    pub fn new() -> Self {
+        Parser
+    }
+
+    /// Parses `lexer`'s text starting from `origin` (used as-is if no `$ORIGIN` appears before
+    /// the first record), returning the zone's final origin and its RRsets
+    pub fn parse(&mut self,
+                 lexer: Lexer,
+                 origin: Option<Name>)
+                 -> ParseResult<(Name, BTreeMap<RrKey, RecordSet>)> {
+        let mut state = ParseState {
+            origin: origin,
+            current_name: None,
+            last_ttl: None,
+            soa_minimum: None,
+            records: BTreeMap::new(),
+        };
+
+        try!(self.parse_lexer(lexer, &mut state));
+
+        let origin = try!(state.origin
+            .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("no origin for zone specified".to_string()))));
+        Ok((origin, state.records))
+    }
+
+    /// Drives one lexer (the top-level file, or an `$INCLUDE`d one) to completion against the
+    /// shared `state`; recurses for `$INCLUDE` so an included file's directives and records are
+    /// applied exactly as if they were spliced into the including file in place
+    fn parse_lexer(&mut self, mut lexer: Lexer, state: &mut ParseState) -> ParseResult<()> {
+        loop {
+            match try!(lexer.next_token()) {
+                None => return Ok(()),
+                Some(Token::EOL) => continue,
+                Some(Token::Origin(name)) => {
+                    state.origin = Some(try!(Self::parse_name(&name, state.origin.as_ref())));
+                }
+                Some(Token::Ttl(ttl)) => {
+                    // per RFC 2308 §4, every TTL-less record after this point defaults to
+                    // `ttl` until either another `$TTL` or an explicit TTL supersedes it, so
+                    // this is tracked the same way an explicit per-record ttl is
+                    state.last_ttl = Some(ttl);
+                }
+                Some(Token::Include(path, origin)) => {
+                    try!(self.apply_include(&path, origin, state));
+                }
+                Some(Token::Generate(fields)) => {
+                    try!(self.expand_generate(fields, state));
+                }
+                Some(Token::Blank) => {
+                    let name = try!(state.current_name.clone()
+                        .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("first record of a zone must have an owner name".to_string()))));
+                    try!(self.parse_record(&mut lexer, state, name));
+                }
+                Some(Token::CharData(name)) => {
+                    let name = try!(Self::parse_name(&name, state.origin.as_ref()));
+                    try!(self.parse_record(&mut lexer, state, name));
+                }
+            }
+        }
+    }
+
+    /// reads (owner already consumed) the optional ttl/class, the type, and the rdata tokens of
+    /// one record, then inserts it into `state.records`
+    fn parse_record(&mut self, lexer: &mut Lexer, state: &mut ParseState, name: Name) -> ParseResult<()> {
+        let mut ttl_field = None;
+        let mut class_field = None;
+
+        let type_str = loop {
+            match try!(lexer.next_token()) {
+                Some(Token::CharData(s)) => {
+                    if ttl_field.is_none() {
+                        if let Ok(ttl) = s.parse::<u32>() {
+                            ttl_field = Some(ttl);
+                            continue;
+                        }
+                    }
+                    if class_field.is_none() {
+                        if let Some(class) = try_parse_class(&s) {
+                            class_field = Some(class);
+                            continue;
+                        }
+                    }
+                    break s;
+                }
+                Some(t) => return Err(ParseErrorKind::Msg(format!("expected a record type for {:?}, found {:?}", name, t)).into()),
+                None => return Err(ParseErrorKind::Msg(format!("record for {:?} is missing a type", name)).into()),
+            }
+        };
+
+        let record_type = try!(parse_record_type(&type_str));
+
+        let mut rdata_tokens = Vec::new();
+        loop {
+            match try!(lexer.next_token()) {
+                Some(Token::EOL) | None => break,
+                Some(t) => rdata_tokens.push(t),
+            }
+        }
+
+        let rdata = try!(RData::parse(record_type, &rdata_tokens, state.origin.as_ref()));
+        let soa_expire = if let RData::SOA(ref soa) = rdata {
+            state.soa_minimum = Some(soa.minimum());
+            Some(soa.expire())
+        } else {
+            None
+        };
+
+        let ttl = match soa_expire {
+            // an SOA with neither its own ttl nor one in effect takes its expire as its ttl;
+            // the records after it still default to the SOA minimum
+            Some(expire) if ttl_field.is_none() && state.last_ttl.is_none() => expire,
+            _ => {
+                let ttl = Self::resolve_ttl(state, ttl_field);
+                state.last_ttl = Some(ttl);
+                ttl
+            }
+        };
+        state.current_name = Some(name.clone());
+
+        let mut record = Record::from_rdata(name.clone(), ttl, rdata);
+        if let Some(class) = class_field {
+            record.set_dns_class(class);
+        }
+
+        Self::insert_record(state, name, record_type, ttl, record);
+        Ok(())
+    }
+
+    /// `$INCLUDE path [origin]`: lexes `path` and parses it into the same `state`, temporarily
+    /// switching the current origin if one was given, then restoring it
+    fn apply_include(&mut self, path: &str, origin: Option<String>, state: &mut ParseState) -> ParseResult<()> {
+        let mut text = String::new();
+        try!(File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| ParseError::from(ParseErrorKind::Msg(format!("failed to read $INCLUDE {}: {}", path, e)))));
+
+        let saved_origin = state.origin.clone();
+        if let Some(origin) = origin {
+            state.origin = Some(try!(Self::parse_name(&origin, saved_origin.as_ref())));
+        }
+
+        try!(self.parse_lexer(Lexer::new(&text), state));
+        state.origin = saved_origin;
+        Ok(())
+    }
+
+    /// `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs`: expands the range directly
+    /// into records, substituting the iteration value for `$` in `lhs` and `rhs`
+    fn expand_generate(&mut self, fields: Vec<String>, state: &mut ParseState) -> ParseResult<()> {
+        let mut fields = fields.into_iter();
+        let range = try!(fields.next()
+            .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("$GENERATE requires a range".to_string()))));
+        let lhs = try!(fields.next()
+            .ok_or_else(|| ParseError::from(ParseErrorKind::Msg("$GENERATE requires a lhs".to_string()))));
+        let mut rest: Vec<String> = fields.collect();
+
+        let ttl_field = if rest.first().map_or(false, |f| f.parse::<u32>().is_ok()) {
+            Some(rest.remove(0).parse().expect("validated by map_or above"))
+        } else {
+            None
+        };
+
+        let class_field = if rest.first().map_or(false, |f| try_parse_class(f).is_some()) {
+            try_parse_class(&rest.remove(0))
+        } else {
+            None
+        };
+
+        if rest.is_empty() {
+            return Err(ParseErrorKind::Msg("$GENERATE requires a type and rhs".to_string()).into());
+        }
+        let record_type = try!(parse_record_type(&rest.remove(0)));
+        let rhs = rest.join(" ");
+
+        let (start, stop, step) = try!(parse_generate_range(&range));
+
+        let mut value = start;
+        loop {
+            if step > 0 && value > stop {
+                break;
+            }
+            if step < 0 && value < stop {
+                break;
+            }
+
+            let name = try!(Self::parse_name(&substitute(&lhs, value), state.origin.as_ref()));
+            let rdata_tokens = vec![Token::CharData(substitute(&rhs, value))];
+            let rdata = try!(RData::parse(record_type, &rdata_tokens, state.origin.as_ref()));
+
+            let ttl = Self::resolve_ttl(state, ttl_field);
+            state.last_ttl = Some(ttl);
+
+            let mut record = Record::from_rdata(name.clone(), ttl, rdata);
+            if let Some(class) = class_field {
+                record.set_dns_class(class);
+            }
+
+            Self::insert_record(state, name, record_type, ttl, record);
+            value += step;
+        }
+
+        Ok(())
+    }
+
+    /// an explicit ttl wins, then the last ttl in effect (the most recent of an explicit ttl or
+    /// a `$TTL` directive), then (matching the pre-`$TTL` convention) the zone's SOA minimum,
+    /// then finally 0
+    fn resolve_ttl(state: &ParseState, explicit: Option<u32>) -> u32 {
+        explicit.or(state.last_ttl).or(state.soa_minimum).unwrap_or(0)
+    }
+
+    fn insert_record(state: &mut ParseState, name: Name, record_type: RecordType, ttl: u32, record: Record) {
+        let key = RrKey::new(name.clone(), record_type);
+        state.records
+            .entry(key)
+            .or_insert_with(move || RecordSet::new(name, record_type, ttl))
+            .insert(record, 0);
+    }
+
+    fn parse_name(s: &str, origin: Option<&Name>) -> ParseResult<Name> {
+        Name::parse(&vec![Token::CharData(s.to_string())], origin)
+    }
+}
+
+struct ParseState {
+    origin: Option<Name>,
+    current_name: Option<Name>,
+    last_ttl: Option<u32>,
+    soa_minimum: Option<u32>,
+    records: BTreeMap<RrKey, RecordSet>,
+}
+
+fn try_parse_class(s: &str) -> Option<DNSClass> {
+    match s.to_uppercase().as_str() {
+        "IN" => Some(DNSClass::IN),
+        "CH" => Some(DNSClass::CH),
+        "HS" => Some(DNSClass::HS),
+        "NONE" => Some(DNSClass::NONE),
+        "ANY" => Some(DNSClass::ANY),
+        _ => None,
+    }
+}
+
+fn parse_record_type(s: &str) -> ParseResult<RecordType> {
+    match s.to_uppercase().as_str() {
+        "A" => Ok(RecordType::A),
+        "AAAA" => Ok(RecordType::AAAA),
+        "CNAME" => Ok(RecordType::CNAME),
+        "MX" => Ok(RecordType::MX),
+        "NS" => Ok(RecordType::NS),
+        "PTR" => Ok(RecordType::PTR),
+        "SOA" => Ok(RecordType::SOA),
+        "SRV" => Ok(RecordType::SRV),
+        "TXT" => Ok(RecordType::TXT),
+        _ => Err(ParseErrorKind::Msg(format!("unsupported record type in master file: {}", s)).into()),
+    }
+}
+
+/// Parses a `$GENERATE` range: `start-stop` or `start-stop/step`. `step` defaults to 1, or -1
+/// if `stop` is before `start`.
+fn parse_generate_range(s: &str) -> ParseResult<(i64, i64, i64)> {
+    let bad_range = || ParseError::from(ParseErrorKind::Msg(format!("invalid $GENERATE range: {}", s)));
+
+    let (range, step) = match s.find('/') {
+        Some(idx) => (&s[..idx], Some(try!(s[idx + 1..].parse::<i64>().map_err(|_| bad_range())))),
+        None => (s, None),
+    };
+
+    let dash = try!(range.find('-').ok_or_else(bad_range));
+    let start: i64 = try!(range[..dash].parse().map_err(|_| bad_range()));
+    let stop: i64 = try!(range[dash + 1..].parse().map_err(|_| bad_range()));
+
+    let step = step.unwrap_or_else(|| if stop < start { -1 } else { 1 });
+    if step == 0 {
+        return Err(bad_range());
+    }
+
+    Ok((start, stop, step))
+}
+
+/// Substitutes `$` with `value` in a `$GENERATE` lhs/rhs pattern: `$$` is a literal `$`, and
+/// `${width}` zero-pads `value` to `width` decimal digits.
+fn substitute(pattern: &str, value: i64) -> String {
+    let mut out = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.peek().cloned() {
+            Some('$') => {
+                chars.next();
+                out.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let mut width = String::new();
+                for c in &mut chars {
+                    if c == '}' {
+                        break;
+                    }
+                    width.push(c);
+                }
+                let width: usize = width.parse().unwrap_or(0);
+                out.push_str(&format!("{:01$}", value, width));
+            }
+            _ => out.push_str(&value.to_string()),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod mytests {
+    use std::fs;
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn a_address(records: &BTreeMap<RrKey, RecordSet>, name: Name) -> Ipv4Addr {
+        let record_set = records.get(&RrKey::new(name, RecordType::A)).expect("no A RRset for that name");
+        match *record_set.records_without_rrsigs().next().expect("RRset has no records").rdata() {
+            RData::A(ref address) => *address.as_a(),
+            ref rdata => panic!("not an A record: {:?}", rdata),
+        }
+    }
+
+    #[test]
+    fn test_generate_range_step_and_substitution() {
+        let origin = Name::new().label("example").label("com");
+        let zone = "$GENERATE 1-5/2 host$ A 192.0.2.$";
+
+        let (_, records) = Parser::new().parse(Lexer::new(zone), Some(origin.clone())).unwrap();
+
+        for i in &[1u8, 3, 5] {
+            let name = Name::new().label(&format!("host{}", i)).label("example").label("com");
+            assert_eq!(Ipv4Addr::new(192, 0, 2, *i), a_address(&records, name));
+        }
+
+        // the /2 step should have skipped 2 and 4
+        assert!(!records.contains_key(&RrKey::new(Name::new().label("host2").label("example").label("com"),
+                                                    RecordType::A)));
+    }
+
+    #[test]
+    fn test_generate_zero_padded_substitution() {
+        let origin = Name::new().label("example").label("com");
+        let zone = "$GENERATE 8-9 host${3} A 192.0.2.$";
+
+        let (_, records) = Parser::new().parse(Lexer::new(zone), Some(origin)).unwrap();
+
+        let name = Name::new().label("host008").label("example").label("com");
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 8), a_address(&records, name));
+    }
+
+    #[test]
+    fn test_ttl_directive_overrides_prior_explicit_ttl() {
+        let origin = Name::new().label("example").label("com");
+        let zone = "rr1 60 IN A 192.0.2.1\n$TTL 3600\nrr2 IN A 192.0.2.2";
+
+        let (_, records) = Parser::new().parse(Lexer::new(zone), Some(origin.clone())).unwrap();
+
+        let rr1 = records.get(&RrKey::new(Name::new().label("rr1").label("example").label("com"), RecordType::A))
+            .unwrap();
+        let rr2 = records.get(&RrKey::new(Name::new().label("rr2").label("example").label("com"), RecordType::A))
+            .unwrap();
+
+        assert_eq!(60, rr1.ttl());
+        assert_eq!(3600, rr2.ttl(), "$TTL did not take effect for a later TTL-less record");
+    }
+
+    #[test]
+    fn test_origin_directive_mid_file() {
+        let zone = "$ORIGIN example.com.\na A 192.0.2.1\n$ORIGIN other.com.\nb A 192.0.2.2";
+
+        let (origin, records) = Parser::new().parse(Lexer::new(zone), None).unwrap();
+
+        assert_eq!(Name::new().label("other").label("com"), origin);
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 1),
+                   a_address(&records, Name::new().label("a").label("example").label("com")));
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 2),
+                   a_address(&records, Name::new().label("b").label("other").label("com")));
+    }
+
+    #[test]
+    fn test_include_with_sub_origin() {
+        let path = ::std::env::temp_dir().join(format!("trust-dns-parser-test-{:?}.zone", ::std::thread::current().id()));
+        fs::write(&path, "host A 192.0.2.9").unwrap();
+
+        let zone = format!("$ORIGIN example.com.\n$INCLUDE {} sub\nafter A 192.0.2.10", path.display());
+        let result = Parser::new().parse(Lexer::new(&zone), None);
+        fs::remove_file(&path).ok();
+        let (origin, records) = result.unwrap();
+
+        // the included file's origin only applies while it's being parsed
+        assert_eq!(Name::new().label("example").label("com"), origin);
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 9),
+                   a_address(&records, Name::new().label("host").label("sub").label("example").label("com")));
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 10),
+                   a_address(&records, Name::new().label("after").label("example").label("com")));
+    }
+}
@@ -0,0 +1,38 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the tokens a zone-file `Lexer` produces and a `Parser` consumes
+
+/// One lexical unit of a master file (RFC 1035 §5), as produced by `Lexer::next_token`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    /// the end of a record: a newline that isn't nested inside `(` `)`
+    EOL,
+    /// a line that begins with whitespace, i.e. the owner name is omitted and the previous
+    /// record's owner should be reused
+    Blank,
+    /// a label, number, quoted string, or `@` (the current origin)
+    CharData(String),
+    /// `$ORIGIN <name>`, the name is exactly as written (not yet qualified)
+    Origin(String),
+    /// `$TTL <ttl>`, already resolved to seconds
+    Ttl(u32),
+    /// `$INCLUDE <path> [origin]`
+    Include(String, Option<String>),
+    /// `$GENERATE <range> <lhs> [ttl] [class] <type> <rhs>`, split on whitespace for the
+    /// parser to interpret positionally
+    Generate(Vec<String>),
+}
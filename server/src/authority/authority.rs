@@ -0,0 +1,474 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Authority is the backing store for a zone, its records, and (optionally) its DNSSEC signing
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use trust_dns::rr::{DNSClass, Name, Record, RData, RecordSet, RecordType, RrKey};
+use trust_dns::rr::dnssec::{Signer, SupportedAlgorithms, SupportedDigests};
+use trust_dns::rr::dnssec::rdata::{self, NSEC, SIG};
+use trust_dns::serialize::binary::BinEncoder;
+use trust_dns::error::{DnsSecResult, DnsSecErrorKind};
+
+use authority::ZoneType;
+
+/// `Authority` is the zone's in-memory store: every RRset loaded from a master file or zone
+/// transfer, plus (when `secure_keys` is non-empty) the machinery to keep that zone's RRSIGs
+/// and NSEC chain current.
+pub struct Authority {
+    origin: Name,
+    records: BTreeMap<RrKey, RecordSet>,
+    zone_type: ZoneType,
+    allow_update: bool,
+    is_dnssec_enabled: bool,
+    secure_keys: Vec<Signer>,
+}
+
+impl Authority {
+    /// Creates a new `Authority` over `records`, loaded for `origin`.
+    ///
+    /// `new` runs `secure_zone`, but no keys are attached at that point, so nothing is signed
+    /// here; keys attached later with `add_secure_key` take effect on the next `secure_zone()`.
+    pub fn new(origin: Name,
+               records: BTreeMap<RrKey, RecordSet>,
+               zone_type: ZoneType,
+               allow_update: bool,
+               is_dnssec_enabled: bool)
+               -> Self {
+        let mut authority = Authority {
+            origin: origin,
+            records: records,
+            zone_type: zone_type,
+            allow_update: allow_update,
+            is_dnssec_enabled: is_dnssec_enabled,
+            secure_keys: Vec::new(),
+        };
+
+        if let Err(e) = authority.secure_zone() {
+            warn!("failed to sign zone on load: {}", e);
+        }
+
+        authority
+    }
+
+    /// The origin of this zone
+    pub fn origin(&self) -> &Name {
+        &self.origin
+    }
+
+    /// The type of zone this authority serves
+    pub fn zone_type(&self) -> ZoneType {
+        self.zone_type
+    }
+
+    /// True if dynamic update (RFC 2136) is allowed against this zone
+    pub fn allow_update(&self) -> bool {
+        self.allow_update
+    }
+
+    /// True if this zone should be kept signed with DNSSEC
+    pub fn is_dnssec_enabled(&self) -> bool {
+        self.is_dnssec_enabled
+    }
+
+    /// Attaches a signing key to this zone. Multiple keys (e.g. a KSK and a ZSK) may be added;
+    /// each zone-signing key produces its own RRSIG over every RRset except DNSKEY, and each
+    /// key-signing key its own RRSIG over the DNSKEY RRset only. Callers should re-run `secure_zone()`
+    /// (or re-create the `Authority`) after adding keys so the new signature set takes effect.
+    pub fn add_secure_key(&mut self, signer: Signer) {
+        self.secure_keys.push(signer);
+    }
+
+    /// The zone's SOA record, if present
+    pub fn soa(&self) -> Option<&Record> {
+        self.lookup(&self.origin.clone(),
+                    RecordType::SOA,
+                    false,
+                    SupportedAlgorithms::new(),
+                    SupportedDigests::new())
+            .first()
+            .cloned()
+    }
+
+    /// Looks up the RRset at `name` of type `record_type`. When `is_dnssec` is true and the
+    /// zone is signed, the covering RRSIGs are included in the result, filtered down to the
+    /// algorithms `supported_algorithms` (as advertised by the querying resolver's EDNS DAU
+    /// option) and this zone's keys have in common. A `DS` lookup is similarly trimmed to the
+    /// single strongest digest among the DS records whose own digest type is in
+    /// `supported_digests` (as advertised by the querying resolver's EDNS DHU option), since a
+    /// resolver only ever needs one it trusts.
+    pub fn lookup(&self,
+                  name: &Name,
+                  record_type: RecordType,
+                  is_dnssec: bool,
+                  supported_algorithms: SupportedAlgorithms,
+                  supported_digests: SupportedDigests)
+                  -> Vec<&Record> {
+        let rr_key = RrKey::new(name.clone(), record_type);
+
+        self.records
+            .get(&rr_key)
+            .map(|rr_set| {
+                let mut records: Vec<&Record> = rr_set.records_without_rrsigs().collect();
+
+                if record_type == RecordType::DS {
+                    records = Self::strongest_digest(records, supported_digests);
+                }
+
+                if is_dnssec {
+                    records.extend(self.filter_rrsigs(rr_set.rrsigs(), supported_algorithms));
+                }
+
+                records
+            })
+            .unwrap_or_else(Vec::new)
+    }
+
+    /// The algorithms this zone currently signs with
+    fn signer_algorithms(&self) -> SupportedAlgorithms {
+        let mut supported = SupportedAlgorithms::new();
+        for signer in &self.secure_keys {
+            supported.set(signer.algorithm());
+        }
+        supported
+    }
+
+    /// Drops RRSIGs whose algorithm the querying resolver didn't advertise support for. If
+    /// that would leave nothing mutually understood (e.g. an unsigned zone, or a resolver
+    /// advertising only algorithms this zone doesn't use), every RRSIG is returned instead of
+    /// answering with none at all.
+    fn filter_rrsigs<'r>(&self,
+                         rrsigs: &'r [Record],
+                         client_algorithms: SupportedAlgorithms)
+                         -> Vec<&'r Record> {
+        let mutually_supported = client_algorithms.intersection(&self.signer_algorithms());
+
+        if mutually_supported.is_empty() {
+            return rrsigs.iter().collect();
+        }
+
+        rrsigs.iter()
+            .filter(|rrsig| match *rrsig.rdata() {
+                RData::SIG(ref sig) => mutually_supported.has(sig.algorithm()),
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// A DS RRset may carry one entry per digest type (SHA-1, SHA-256, ...); returning all of
+    /// them needlessly bloats the response, so keep only the one with the highest digest type,
+    /// which by convention is also the strongest. Preference goes first to DS records whose own
+    /// `digest_type` the resolver advertised understanding (via `supported_digests`); if none of
+    /// them did, every DS record is still a candidate rather than answering with nothing, the
+    /// same fallback `filter_rrsigs` uses.
+    fn strongest_digest(records: Vec<&Record>, supported_digests: SupportedDigests) -> Vec<&Record> {
+        let mutually_supported: Vec<&Record> = records.iter()
+            .filter(|record| match *record.rdata() {
+                RData::DS(ref ds) => supported_digests.has(ds.digest_type()),
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        let candidates = if mutually_supported.is_empty() {
+            records
+        } else {
+            mutually_supported
+        };
+
+        candidates.into_iter()
+            .max_by_key(|record| match *record.rdata() {
+                RData::DS(ref ds) => ds.digest_type(),
+                _ => 0,
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// (Re)signs the zone: bumps the SOA serial, rebuilds the NSEC chain for authenticated
+    /// denial of existence, and produces a fresh RRSIG per RRset per configured key. A no-op
+    /// if no keys have been attached with `add_secure_key`.
+    ///
+    /// This should be called once the zone is loaded and its keys are attached (the call in `new`
+    /// comes before any key can be attached, so it signs nothing) and again after every dynamic update that changes the zone's records, so that served
+    /// signatures never drift from the records they cover.
+    pub fn secure_zone(&mut self) -> DnsSecResult<()> {
+        if self.secure_keys.is_empty() {
+            return Ok(());
+        }
+
+        self.increment_soa_serial();
+        self.publish_dnskey_rrset();
+        self.rebuild_nsec_chain();
+
+        let signed = try!(self.sign_rrsets());
+        for (rr_key, rrsigs) in signed {
+            if let Some(rr_set) = self.records.get_mut(&rr_key) {
+                rr_set.clear_rrsigs();
+                for rrsig in rrsigs {
+                    rr_set.insert_rrsig(rrsig);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn increment_soa_serial(&mut self) {
+        let rr_key = RrKey::new(self.origin.clone(), RecordType::SOA);
+
+        if let Some(rr_set) = self.records.get_mut(&rr_key) {
+            for record in rr_set.records_without_rrsigs_mut() {
+                if let RData::SOA(ref mut soa) = *record.rdata_mut() {
+                    soa.increment_serial();
+                }
+            }
+        }
+    }
+
+    /// (Re)publishes the zone apex's DNSKEY RRset from every attached key, one DNSKEY record
+    /// per key (both key-signing and zone-signing keys are published; only a KSK's own RRSIG
+    /// later covers the RRset, per `sign_rrsets`).
+    fn publish_dnskey_rrset(&mut self) {
+        let ttl = self.minimum_ttl();
+        let rr_key = RrKey::new(self.origin.clone(), RecordType::DNSKEY);
+
+        let mut rr_set = RecordSet::new(self.origin.clone(), RecordType::DNSKEY, ttl);
+        for signer in &self.secure_keys {
+            if let Ok(dnskey) = signer.to_dnskey() {
+                rr_set.insert(Record::from_rdata(self.origin.clone(), ttl, RData::DNSKEY(dnskey)), 0);
+            }
+        }
+        self.records.insert(rr_key, rr_set);
+    }
+
+    fn minimum_ttl(&self) -> u32 {
+        self.soa()
+            .and_then(|soa| match *soa.rdata() {
+                RData::SOA(ref rdata) => Some(rdata.minimum()),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Rebuilds the NSEC chain from scratch: one NSEC record per distinct owner name in the
+    /// zone, sorted in canonical order, each pointing at the name that canonically follows it
+    /// (wrapping back to the apex), with a type bitmap of what exists at that name.
+    fn rebuild_nsec_chain(&mut self) {
+        let ttl = self.minimum_ttl();
+
+        let mut names: Vec<Name> = self.records.keys().map(|key| key.name().clone()).collect();
+        names.sort();
+        names.dedup();
+
+        for (i, name) in names.iter().enumerate() {
+            let next_name = names.get(i + 1).cloned().unwrap_or_else(|| self.origin.clone());
+
+            let mut types: Vec<RecordType> = self.records
+                .keys()
+                .filter(|key| key.name() == name)
+                .map(|key| key.record_type())
+                .collect();
+            types.push(RecordType::NSEC);
+            types.push(RecordType::RRSIG);
+            types.sort_by_key(RecordType::to_u16);
+            types.dedup();
+
+            let record = Record::from_rdata(name.clone(), ttl, RData::NSEC(NSEC::new(next_name, types)));
+
+            let mut rr_set = RecordSet::new(name.clone(), RecordType::NSEC, ttl);
+            rr_set.insert(record, 0);
+            self.records.insert(RrKey::new(name.clone(), RecordType::NSEC), rr_set);
+        }
+    }
+
+    fn sign_rrsets(&self) -> DnsSecResult<Vec<(RrKey, Vec<Record>)>> {
+        let mut signed = Vec::with_capacity(self.records.len());
+
+        for (rr_key, rr_set) in &self.records {
+            // RRSIGs don't cover themselves
+            if rr_key.record_type() == RecordType::RRSIG {
+                continue;
+            }
+
+            // a key-signing key only covers the zone's DNSKEY RRset; a zone-signing key
+            // covers everything else, per RFC 4034 Appendix A.1's "Zone Key"/"Secure Entry
+            // Point" flag convention
+            let is_dnskey = rr_key.record_type() == RecordType::DNSKEY;
+            let signers = self.secure_keys
+                .iter()
+                .filter(|signer| signer.is_zone_signing_key() != is_dnskey);
+
+            let mut rrsigs = Vec::with_capacity(self.secure_keys.len());
+            for signer in signers {
+                rrsigs.push(try!(self.sign_rrset(rr_key, rr_set, signer)));
+            }
+            signed.push((rr_key.clone(), rrsigs));
+        }
+
+        Ok(signed)
+    }
+
+    fn sign_rrset(&self, rr_key: &RrKey, rr_set: &RecordSet, signer: &Signer) -> DnsSecResult<Record> {
+        let inception = current_time();
+        let expiration = inception + signer.sig_duration().as_secs() as u32;
+
+        let mut sig_rdata = SIG::new(rr_key.record_type(),
+                                      signer.algorithm(),
+                                      rr_key.name().num_labels(),
+                                      rr_set.ttl(),
+                                      expiration,
+                                      inception,
+                                      signer.key_tag(),
+                                      signer.signer_name().clone(),
+                                      Vec::new());
+
+        let tbs = try!(canonical_rrset_tbs(&sig_rdata, rr_set));
+        let signature = try!(signer.sign(&tbs));
+        sig_rdata = SIG::new(sig_rdata.type_covered(),
+                              sig_rdata.algorithm(),
+                              sig_rdata.num_labels(),
+                              sig_rdata.original_ttl(),
+                              sig_rdata.sig_expiration(),
+                              sig_rdata.sig_inception(),
+                              sig_rdata.key_tag(),
+                              sig_rdata.signer_name().clone(),
+                              signature);
+
+        Ok(Record::from_rdata(rr_key.name().clone(), rr_set.ttl(), RData::SIG(sig_rdata)))
+    }
+}
+
+impl fmt::Debug for Authority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Authority")
+            .field("origin", &self.origin)
+            .field("zone_type", &self.zone_type)
+            .field("is_dnssec_enabled", &self.is_dnssec_enabled)
+            .field("rrsets", &self.records.len())
+            .finish()
+    }
+}
+
+fn current_time() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+/// Builds the bytes to be signed for one RRset: the RRSIG RDATA (with an empty signature field)
+/// followed by every record in the set in DNSSEC canonical form — owner name lowercased, RDATA
+/// in canonical form, records sorted by their canonical RDATA encoding, and the RRset's TTL
+/// (not each record's original TTL) used for every record, per RFC 4034 §6.2/6.3.
+fn canonical_rrset_tbs(sig_rdata: &SIG, rr_set: &RecordSet) -> DnsSecResult<Vec<u8>> {
+    let mut tbs = Vec::new();
+    {
+        let mut encoder = BinEncoder::new(&mut tbs);
+        encoder.set_canonical_names(true);
+        try!(rdata::sig::emit(&mut encoder, sig_rdata)
+            .map_err(|e| DnsSecErrorKind::Msg(format!("failed to encode rrsig prefix: {}", e))));
+    }
+    let owner = rr_set.name().to_lowercase();
+
+    let mut encoded: Vec<(Vec<u8>, Record)> = Vec::with_capacity(rr_set.records_without_rrsigs().count());
+    for record in rr_set.records_without_rrsigs() {
+        let mut rdata_buf = Vec::new();
+        {
+            let mut encoder = BinEncoder::new(&mut rdata_buf);
+            encoder.set_canonical_names(true);
+            try!(record.rdata()
+                .emit(&mut encoder)
+                .map_err(|e| DnsSecErrorKind::Msg(format!("failed to encode rdata: {}", e))));
+        }
+        encoded.push((rdata_buf, record.clone()));
+    }
+    encoded.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (rdata_buf, _) in encoded {
+        let mut record_buf = Vec::new();
+        {
+            let mut encoder = BinEncoder::new(&mut record_buf);
+            encoder.set_canonical_names(true);
+            try!(owner.emit(&mut encoder)
+                .map_err(|e| DnsSecErrorKind::Msg(format!("failed to encode owner name: {}", e))));
+            try!(encoder.emit_u16(rr_set.record_type().to_u16())
+                .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+            try!(encoder.emit_u16(DNSClass::IN.to_u16())
+                .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+            try!(encoder.emit_u32(rr_set.ttl())
+                .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+            try!(encoder.emit_u16(rdata_buf.len() as u16)
+                .map_err(|e| DnsSecErrorKind::Msg(format!("{}", e))));
+        }
+
+        tbs.extend_from_slice(&record_buf);
+        tbs.extend_from_slice(&rdata_buf);
+    }
+
+    Ok(tbs)
+}
+
+#[cfg(test)]
+mod mytests {
+    use trust_dns::rr::dnssec::Algorithm;
+    use trust_dns::rr::dnssec::rdata::DS;
+
+    use super::*;
+
+    fn ds_record(digest_type: u8) -> Record {
+        let rdata = DS::new(0, Algorithm::RSASHA256, digest_type, vec![0xAB]);
+        Record::from_rdata(Name::new().label("example").label("com"), 3600, RData::DS(rdata))
+    }
+
+    #[test]
+    fn test_strongest_digest_prefers_mutually_supported() {
+        let sha1 = ds_record(1);
+        let sha256 = ds_record(2);
+        let records = vec![&sha1, &sha256];
+
+        // the resolver only advertised understanding SHA-1 (digest type 1), so even though
+        // SHA-256 (2) has the higher digest type, it isn't a candidate at all
+        let mut supported_digests = SupportedDigests::new();
+        supported_digests.set(1);
+
+        let strongest = Authority::strongest_digest(records, supported_digests);
+        assert_eq!(strongest.len(), 1);
+        match *strongest[0].rdata() {
+            RData::DS(ref ds) => assert_eq!(ds.digest_type(), 1),
+            _ => panic!("expected a DS"),
+        }
+    }
+
+    #[test]
+    fn test_strongest_digest_falls_back_to_highest_when_none_mutually_supported() {
+        let sha1 = ds_record(1);
+        let sha256 = ds_record(2);
+        let records = vec![&sha1, &sha256];
+
+        let supported_digests = SupportedDigests::new();
+
+        let strongest = Authority::strongest_digest(records, supported_digests);
+        assert_eq!(strongest.len(), 1);
+        match *strongest[0].rdata() {
+            RData::DS(ref ds) => assert_eq!(ds.digest_type(), 2),
+            _ => panic!("expected a DS"),
+        }
+    }
+}
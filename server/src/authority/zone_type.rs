@@ -0,0 +1,30 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the type of zone, i.e. authoritative, secondary, or a cache
+
+/// The type of zone stored by an `Authority`
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum ZoneType {
+    /// This server is authoritative for the zone and owns the master copy
+    Master,
+    /// This server is authoritative for the zone as a secondary, loaded via AXFR/IXFR
+    Slave,
+    /// A cached zone with recursive resolution enabled
+    Hint,
+    /// A zone where all requests are forwarded to another resolver
+    Forward,
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! declarative configuration for a single zone-signing key
+
+use std::path::{Path, PathBuf};
+
+use trust_dns::error::DnsSecResult;
+use trust_dns::rr::Name;
+use trust_dns::rr::dnssec::{Algorithm, Signer};
+
+/// One signing key to load for a zone: where its private key material lives on disk, which
+/// algorithm it signs with, and whether it's a key-signing key (which only signs the zone's
+/// DNSKEY RRset) or a zone-signing key (which signs everything else). A zone typically
+/// configures one of each.
+#[derive(Debug, Clone)]
+pub struct KeyConfig {
+    key_path: PathBuf,
+    algorithm: Algorithm,
+    is_zone_signing_key: bool,
+}
+
+impl KeyConfig {
+    /// Creates a new key config. If no key exists at `key_path` yet, `try_into_signer` will
+    /// generate one for `algorithm` and persist it there.
+    pub fn new<P: Into<PathBuf>>(key_path: P, algorithm: Algorithm, is_zone_signing_key: bool) -> Self {
+        KeyConfig {
+            key_path: key_path.into(),
+            algorithm: algorithm,
+            is_zone_signing_key: is_zone_signing_key,
+        }
+    }
+
+    /// The path this key is loaded from (or generated and persisted to, if missing)
+    pub fn key_path(&self) -> &Path {
+        &self.key_path
+    }
+
+    /// The algorithm this key signs with
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// True for a zone-signing key, false for a key-signing key
+    pub fn is_zone_signing_key(&self) -> bool {
+        self.is_zone_signing_key
+    }
+
+    /// Loads (generating and persisting first, if `key_path` doesn't exist) the private key
+    /// this config describes, wrapping it as a `Signer` that names `zone_name` as the signer.
+    pub fn try_into_signer(&self, zone_name: Name) -> DnsSecResult<Signer> {
+        Signer::from_key_path(&self.key_path, self.algorithm, zone_name, self.is_zone_signing_key)
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! declarative configuration for a zone's signing keys
+
+use trust_dns::error::DnsSecResult;
+
+use authority::Authority;
+use config::KeyConfig;
+
+/// A zone's signing configuration: the keys (e.g. a KSK and a ZSK) that should be attached to
+/// its `Authority`. Everything else about a zone (origin, records, zone type) comes from the
+/// master file itself, so this is currently just a list of keys.
+#[derive(Debug, Clone, Default)]
+pub struct ZoneConfig {
+    keys: Vec<KeyConfig>,
+}
+
+impl ZoneConfig {
+    /// An empty configuration: no keys, i.e. an unsigned zone
+    pub fn new() -> Self {
+        ZoneConfig { keys: Vec::new() }
+    }
+
+    /// The keys configured for this zone
+    pub fn keys(&self) -> &[KeyConfig] {
+        &self.keys
+    }
+
+    /// Adds a key to this zone's configuration
+    pub fn add_key(&mut self, key: KeyConfig) -> &mut Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Loads every configured key, attaches each to `authority` via `add_secure_key`, and
+    /// re-signs the zone so that the newly attached keys take effect immediately. This is how
+    /// a master zone parsed from a file gets signed from a declarative config, rather than a
+    /// caller constructing `Signer`s by hand.
+    pub fn secure_authority(&self, authority: &mut Authority) -> DnsSecResult<()> {
+        for key in &self.keys {
+            let signer = try!(key.try_into_signer(authority.origin().clone()));
+            authority.add_secure_key(signer);
+        }
+
+        authority.secure_zone()
+    }
+}
@@ -0,0 +1,74 @@
+extern crate openssl;
+extern crate trust_dns;
+extern crate trust_dns_server;
+
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+
+use trust_dns::rr::*;
+use trust_dns::rr::dnssec::*;
+use trust_dns::serialize::txt::*;
+use trust_dns_server::authority::*;
+
+#[test]
+fn test_secure_zone_publishes_dnskey_nsec_and_rrsig() {
+    let lexer = Lexer::new("@   IN  SOA     venera      action\\.domains (
+                               20     ; SERIAL
+                               7200   ; REFRESH
+                               600    ; RETRY
+                               3600000; EXPIRE
+                               60)    ; MINIMUM
+
+      NS      a.isi.edu.
+      a       A       26.3.0.103");
+
+    let (origin, records) = Parser::new()
+        .parse(lexer, Some(Name::new().label("isi").label("edu")))
+        .expect("failed to parse zone");
+
+    let mut authority = Authority::new(origin, records, ZoneType::Master, false, true);
+
+    let rsa = Rsa::generate(2048).expect("failed to generate key");
+    let key = PKey::from_rsa(rsa).expect("failed to wrap key");
+    let signer = Signer::with_defaults(key,
+                                        Algorithm::RSASHA256,
+                                        Name::new().label("isi").label("edu"),
+                                        true);
+    let mut supported_algorithms = SupportedAlgorithms::new();
+    supported_algorithms.set(Algorithm::RSASHA256);
+
+    authority.add_secure_key(signer);
+    authority.secure_zone().expect("failed to sign zone");
+
+    let apex = Name::new().label("isi").label("edu");
+
+    // DNSKEY
+    let dnskey_records = authority.lookup(&apex,
+                                           RecordType::DNSKEY,
+                                           false,
+                                           SupportedAlgorithms::new(),
+                                           SupportedDigests::new());
+    assert_eq!(1, dnskey_records.len());
+    if let RData::DNSKEY(ref dnskey) = *dnskey_records[0].rdata() {
+        assert_eq!(Algorithm::RSASHA256, dnskey.algorithm());
+    } else {
+        panic!("Not a DNSKEY record!!!") // valid panic, test code
+    }
+
+    // NSEC, at the name that owns the A record
+    let a_name = Name::new().label("a").label("isi").label("edu");
+    let nsec_records = authority.lookup(&a_name,
+                                         RecordType::NSEC,
+                                         false,
+                                         SupportedAlgorithms::new(),
+                                         SupportedDigests::new());
+    assert_eq!(1, nsec_records.len());
+
+    // RRSIG covering the A record, filtered down to the algorithm the resolver understands
+    let a_records = authority.lookup(&a_name, RecordType::A, true, supported_algorithms, SupportedDigests::new());
+    let rrsig_covers_a = a_records.iter().any(|record| match *record.rdata() {
+        RData::SIG(ref sig) => sig.type_covered() == RecordType::A,
+        _ => false,
+    });
+    assert!(rrsig_covers_a, "expected an RRSIG covering the A RRset: {:?}", a_records);
+}
@@ -152,7 +152,7 @@ venera  A       10.1.0.52
     assert_eq!(DNSClass::IN, a_record.dns_class());
     assert_eq!(RecordType::A, a_record.rr_type());
     if let RData::A(ref address) = *a_record.rdata() {
-        assert_eq!(&Ipv4Addr::new(26u8, 3u8, 0u8, 103u8), address);
+        assert_eq!(&Ipv4Addr::new(26u8, 3u8, 0u8, 103u8), address.as_a());
     } else {
         panic!("Not an A record!!!") // valid panic, test code
     }
@@ -170,7 +170,7 @@ venera  A       10.1.0.52
                aaaa_record.name());
     if let RData::AAAA(ref address) = *aaaa_record.rdata() {
         assert_eq!(&Ipv6Addr::from_str("4321:0:1:2:3:4:567:89ab").unwrap(),
-                   address);
+                   address.as_aaaa());
     } else {
         panic!("Not a AAAA record!!!") // valid panic, test code
     }
@@ -188,7 +188,7 @@ venera  A       10.1.0.52
                short_record.name());
     assert_eq!(70, short_record.ttl());
     if let RData::A(ref address) = *short_record.rdata() {
-        assert_eq!(&Ipv4Addr::new(26u8, 3u8, 0u8, 104u8), address);
+        assert_eq!(&Ipv4Addr::new(26u8, 3u8, 0u8, 104u8), address.as_a());
     } else {
         panic!("Not an A record!!!") // valid panic, test code
     }
@@ -229,13 +229,7 @@ venera  A       10.1.0.52
     }
 
     // PTR
-    let ptr_record: &Record = authority.lookup(&Name::new()
-                    .label("103")
-                    .label("0")
-                    .label("3")
-                    .label("26")
-                    .label("in-addr")
-                    .label("arpa"),
+    let ptr_record: &Record = authority.lookup(&Name::from(Ipv4Addr::new(26, 3, 0, 103)),
                 RecordType::PTR,
                 false,
                 SupportedAlgorithms::new())